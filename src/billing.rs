@@ -0,0 +1,110 @@
+use crate::config::{BillingConfig, Rounding, RoundingScope, WorkWeekConfig};
+use crate::document::Day;
+use crate::document::Line::{ClosedShift, SpecialShift};
+use crate::timezone::elapsed;
+use chrono::{Duration, NaiveDate, NaiveTime};
+
+/// A shift, or a group of shifts, that is billed as one
+#[derive(Debug, Clone, PartialEq)]
+pub struct BilledEntry {
+    pub date: NaiveDate,
+    /// `work` for regular shifts, otherwise the text of the special shift. A day with
+    /// several kinds of shifts has them all, separated by commas.
+    pub kind: String,
+    /// Only set when the entry is a single shift
+    pub start: Option<NaiveTime>,
+    pub stop: Option<NaiveTime>,
+    pub note: String,
+    pub raw: Duration,
+    pub rounded: Duration,
+}
+
+/// Rounds a duration to a multiple of the granularity
+pub(crate) fn round(duration: Duration, rounding: Rounding, granularity: Duration) -> Duration {
+    let granularity = granularity.num_minutes();
+    if granularity <= 0 {
+        return duration;
+    }
+    let minutes = duration.num_minutes();
+    let rounded = match rounding {
+        Rounding::Down => minutes.div_euclid(granularity),
+        Rounding::Up => (minutes + granularity - 1).div_euclid(granularity),
+        Rounding::Nearest => (minutes + granularity / 2).div_euclid(granularity),
+    } * granularity;
+    Duration::minutes(rounded)
+}
+
+/// The closed and special shifts of the days, grouped and rounded as configured. Special
+/// days are not billed.
+pub(crate) fn billed_entries(
+    days: &[Day],
+    workweek: &WorkWeekConfig,
+    billing: &BillingConfig,
+) -> Vec<BilledEntry> {
+    let shifts: Vec<BilledEntry> = days
+        .iter()
+        .flat_map(|day| shift_entries(day, workweek))
+        .collect();
+    let mut entries: Vec<BilledEntry> = Vec::new();
+    for shift in shifts {
+        let group = entries.iter_mut().find(|entry| match billing.per {
+            RoundingScope::Shift => false,
+            RoundingScope::Day => entry.date == shift.date,
+            RoundingScope::ProjectDay => entry.date == shift.date && entry.kind == shift.kind,
+        });
+        match group {
+            Some(entry) => {
+                if !entry.kind.split(", ").any(|kind| kind == shift.kind) {
+                    entry.kind = format!("{}, {}", entry.kind, shift.kind);
+                }
+                entry.start = None;
+                entry.stop = None;
+                entry.note = [entry.note.as_str(), shift.note.as_str()]
+                    .into_iter()
+                    .filter(|note| !note.is_empty())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                entry.raw += shift.raw;
+            }
+            None => entries.push(shift),
+        }
+    }
+    for entry in &mut entries {
+        entry.rounded = round(entry.raw, billing.rounding, billing.granularity);
+    }
+    entries
+}
+
+fn shift_entries(day: &Day, workweek: &WorkWeekConfig) -> Vec<BilledEntry> {
+    day.lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (kind, start_time, stop_time) = match line {
+                ClosedShift {
+                    start_time,
+                    stop_time,
+                } => (String::from("work"), *start_time, *stop_time),
+                SpecialShift {
+                    text,
+                    start_time,
+                    stop_time,
+                } => (text.to_lowercase(), *start_time, *stop_time),
+                _ => return None,
+            };
+            let raw = elapsed(day, start_time, stop_time, workweek);
+            Some(BilledEntry {
+                date: day.date,
+                kind,
+                start: Some(start_time),
+                stop: Some(stop_time),
+                note: day.note_for_line(index),
+                raw,
+                rounded: raw,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,132 @@
+use chrono::Duration;
+
+use crate::billing::{billed_entries, round};
+use crate::config::{BillingConfig, Rounding, RoundingScope, WorkWeekConfig};
+use crate::document::{Day, Line};
+use crate::testutils::{naive_date, naive_time};
+
+#[test]
+fn rounds_to_granularity() {
+    let quarter = Duration::minutes(15);
+    assert_eq!(
+        Duration::minutes(60),
+        round(Duration::minutes(67), Rounding::Nearest, quarter)
+    );
+    assert_eq!(
+        Duration::minutes(75),
+        round(Duration::minutes(68), Rounding::Nearest, quarter)
+    );
+    assert_eq!(
+        Duration::minutes(75),
+        round(Duration::minutes(61), Rounding::Up, quarter)
+    );
+    assert_eq!(
+        Duration::minutes(60),
+        round(Duration::minutes(74), Rounding::Down, quarter)
+    );
+    assert_eq!(
+        Duration::minutes(60),
+        round(Duration::minutes(60), Rounding::Up, quarter)
+    );
+}
+
+fn example_days() -> Vec<Day> {
+    vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![
+            Line::ClosedShift {
+                start_time: naive_time(8, 0),
+                stop_time: naive_time(8, 50),
+            },
+            Line::SpecialShift {
+                text: String::from("Acme"),
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(9, 20),
+            },
+            Line::ClosedShift {
+                start_time: naive_time(10, 0),
+                stop_time: naive_time(10, 5),
+            },
+            Line::SpecialDay {
+                text: String::from("Vacation"),
+            },
+        ],
+    }]
+}
+
+fn rounded(billing: &BillingConfig) -> Vec<(String, Duration, Duration)> {
+    billed_entries(&example_days(), &WorkWeekConfig::default(), billing)
+        .into_iter()
+        .map(|entry| (entry.kind, entry.raw, entry.rounded))
+        .collect()
+}
+
+#[test]
+fn rounds_each_shift() {
+    let billing = BillingConfig {
+        rounding: Rounding::Up,
+        granularity: Duration::minutes(15),
+        per: RoundingScope::Shift,
+    };
+    assert_eq!(
+        vec![
+            (
+                String::from("work"),
+                Duration::minutes(50),
+                Duration::minutes(60)
+            ),
+            (
+                String::from("acme"),
+                Duration::minutes(20),
+                Duration::minutes(30)
+            ),
+            (
+                String::from("work"),
+                Duration::minutes(5),
+                Duration::minutes(15)
+            ),
+        ],
+        rounded(&billing)
+    );
+}
+
+#[test]
+fn rounds_each_day() {
+    let billing = BillingConfig {
+        rounding: Rounding::Up,
+        granularity: Duration::minutes(15),
+        per: RoundingScope::Day,
+    };
+    assert_eq!(
+        vec![(
+            String::from("work, acme"),
+            Duration::minutes(75),
+            Duration::minutes(75)
+        )],
+        rounded(&billing)
+    );
+}
+
+#[test]
+fn rounds_each_project_on_each_day() {
+    let billing = BillingConfig {
+        rounding: Rounding::Up,
+        granularity: Duration::minutes(15),
+        per: RoundingScope::ProjectDay,
+    };
+    assert_eq!(
+        vec![
+            (
+                String::from("work"),
+                Duration::minutes(55),
+                Duration::minutes(60)
+            ),
+            (
+                String::from("acme"),
+                Duration::minutes(20),
+                Duration::minutes(30)
+            ),
+        ],
+        rounded(&billing)
+    );
+}
@@ -0,0 +1,157 @@
+use crate::config::{ComplianceConfig, WorkWeekConfig};
+use crate::document::Day;
+use crate::document::Line::ClosedShift;
+use chrono::{Duration, IsoWeek, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+/// A worked shift, with the stop on the next day if it ended after midnight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkedShift {
+    pub start: NaiveDateTime,
+    pub stop: NaiveDateTime,
+}
+
+/// A breach of the working-time rules
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Too little rest between the last shift of a day and the first shift of the next day
+    DailyRest {
+        before: WorkedShift,
+        after: WorkedShift,
+        rest: Duration,
+    },
+    /// No long enough uninterrupted rest during a week
+    WeeklyRest {
+        week: IsoWeek,
+        longest_rest: Duration,
+    },
+    /// Too much work on average over the reference period ending with a week
+    AverageWeek {
+        first_week: IsoWeek,
+        last_week: IsoWeek,
+        average: Duration,
+    },
+}
+
+impl Violation {
+    /// The date the violation is reported on, which for weeks is their last day
+    pub fn date(&self, workweek: &WorkWeekConfig) -> NaiveDate {
+        match self {
+            Violation::DailyRest { after, .. } => after.start.date(),
+            Violation::WeeklyRest { week, .. }
+            | Violation::AverageWeek {
+                last_week: week, ..
+            } => workweek.first_day(*week) + TimeDelta::try_days(6).unwrap(),
+        }
+    }
+}
+
+/// Checks the shifts of the days against the rules. Only rests ending and weeks ending from
+/// `from` up until `now` are checked, but the days should include the reference period
+/// before that. Weeks start on the day the work week starts on.
+pub(crate) fn check(
+    days: &[Day],
+    from: NaiveDate,
+    now: NaiveDateTime,
+    config: &ComplianceConfig,
+    workweek: &WorkWeekConfig,
+) -> Vec<Violation> {
+    let shifts = worked_shifts(days);
+    let mut violations = daily_rest_violations(&shifts, config);
+    violations.retain(|violation| violation.date(workweek) >= from);
+
+    let mut first_day = workweek.first_day(workweek.week_of(from));
+    while (first_day + TimeDelta::try_days(7).unwrap()).and_time(NaiveTime::MIN) <= now {
+        let week = workweek.week_of(first_day);
+        let longest_rest = longest_rest_in_week(&shifts, first_day);
+        if longest_rest < config.weekly_rest {
+            violations.push(Violation::WeeklyRest { week, longest_rest });
+        }
+        let first_day_of_period =
+            first_day - TimeDelta::try_weeks(config.reference_weeks as i64 - 1).unwrap();
+        let average = worked_between(
+            &shifts,
+            first_day_of_period,
+            first_day + TimeDelta::try_days(7).unwrap(),
+        ) / config.reference_weeks as i32;
+        if average > config.max_average_week {
+            violations.push(Violation::AverageWeek {
+                first_week: workweek.week_of(first_day_of_period),
+                last_week: week,
+                average,
+            });
+        }
+        first_day += TimeDelta::try_days(7).unwrap();
+    }
+
+    violations.sort_by_key(|violation| violation.date(workweek));
+    violations
+}
+
+fn worked_shifts(days: &[Day]) -> Vec<WorkedShift> {
+    let mut shifts: Vec<WorkedShift> = days
+        .iter()
+        .flat_map(|day| {
+            day.lines.iter().filter_map(|line| match line {
+                ClosedShift {
+                    start_time,
+                    stop_time,
+                } => Some(WorkedShift {
+                    start: day.date.and_time(*start_time),
+                    stop: if stop_time < start_time {
+                        (day.date + TimeDelta::try_days(1).unwrap()).and_time(*stop_time)
+                    } else {
+                        day.date.and_time(*stop_time)
+                    },
+                }),
+                _ => None,
+            })
+        })
+        .collect();
+    shifts.sort_by_key(|shift| shift.start);
+    shifts
+}
+
+fn daily_rest_violations(shifts: &[WorkedShift], config: &ComplianceConfig) -> Vec<Violation> {
+    shifts
+        .windows(2)
+        .filter_map(|pair| {
+            let (before, after) = (pair[0], pair[1]);
+            let rest = after.start - before.stop;
+            // Breaks during a day are not rests; only look at where one day's work ends
+            let next_day =
+                after.start.date() == before.start.date() + TimeDelta::try_days(1).unwrap();
+            (next_day && rest < config.daily_rest).then_some(Violation::DailyRest {
+                before,
+                after,
+                rest,
+            })
+        })
+        .collect()
+}
+
+/// The longest time without work within the week starting on a certain day
+fn longest_rest_in_week(shifts: &[WorkedShift], first_day: NaiveDate) -> Duration {
+    let week_start = first_day.and_time(NaiveTime::MIN);
+    let week_end = week_start + TimeDelta::try_days(7).unwrap();
+    let mut longest = Duration::zero();
+    let mut rest_start = week_start;
+    for shift in shifts {
+        if shift.stop <= week_start || shift.start >= week_end {
+            continue;
+        }
+        longest = longest.max(shift.start - rest_start);
+        rest_start = rest_start.max(shift.stop);
+    }
+    longest.max(week_end - rest_start)
+}
+
+fn worked_between(shifts: &[WorkedShift], from: NaiveDate, until: NaiveDate) -> Duration {
+    shifts
+        .iter()
+        .filter(|shift| shift.start.date() >= from && shift.start.date() < until)
+        .map(|shift| shift.stop - shift.start)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests;
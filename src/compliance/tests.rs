@@ -0,0 +1,158 @@
+use chrono::{Duration, NaiveTime, Weekday};
+
+use crate::compliance::{Violation, WorkedShift, check};
+use crate::config::{ComplianceConfig, WorkWeekConfig};
+use crate::document::{Day, Line};
+use crate::testutils::{iso_week, naive_date, naive_date_time};
+
+/// A day in january 2024 with shifts like `"08:00-12:00"`
+fn day(date: u32, shifts: &[&str]) -> Day {
+    Day {
+        date: naive_date(2024, 1, date),
+        lines: shifts
+            .iter()
+            .map(|shift| {
+                let (start, stop) = shift.split_once('-').unwrap();
+                let time = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+                Line::ClosedShift {
+                    start_time: time(start),
+                    stop_time: time(stop),
+                }
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn regular_weeks_comply() {
+    let days: Vec<Day> = (22..=26)
+        .map(|date| day(date, &["08:00-12:00", "13:00-17:00"]))
+        .collect();
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 22),
+        naive_date_time(2024, 1, 29, 12, 0),
+        &ComplianceConfig::default(),
+        &WorkWeekConfig::default(),
+    );
+
+    assert_eq!(Vec::<Violation>::new(), violations);
+}
+
+#[test]
+fn too_short_daily_rest_is_a_violation() {
+    let days = vec![day(22, &["14:00-22:30"]), day(23, &["07:00-12:00"])];
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 22),
+        naive_date_time(2024, 1, 24, 12, 0),
+        &ComplianceConfig::default(),
+        &WorkWeekConfig::default(),
+    );
+
+    assert_eq!(
+        vec![Violation::DailyRest {
+            before: WorkedShift {
+                start: naive_date_time(2024, 1, 22, 14, 0),
+                stop: naive_date_time(2024, 1, 22, 22, 30),
+            },
+            after: WorkedShift {
+                start: naive_date_time(2024, 1, 23, 7, 0),
+                stop: naive_date_time(2024, 1, 23, 12, 0),
+            },
+            rest: Duration::minutes(510),
+        }],
+        violations
+    );
+}
+
+#[test]
+fn too_short_weekly_rest_is_a_violation() {
+    // Working every day from 08:00 to 20:00 leaves at most 12 hours of rest
+    let days: Vec<Day> = (22..=28).map(|date| day(date, &["08:00-20:00"])).collect();
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 22),
+        naive_date_time(2024, 1, 29, 12, 0),
+        &ComplianceConfig::default(),
+        &WorkWeekConfig::default(),
+    );
+
+    assert!(violations.contains(&Violation::WeeklyRest {
+        week: iso_week(2024, 4),
+        longest_rest: Duration::hours(12),
+    }));
+}
+
+#[test]
+fn weeks_that_have_not_ended_are_not_checked_for_weekly_rest() {
+    let days: Vec<Day> = (22..=27).map(|date| day(date, &["08:00-20:00"])).collect();
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 22),
+        naive_date_time(2024, 1, 28, 12, 0),
+        &ComplianceConfig::default(),
+        &WorkWeekConfig::default(),
+    );
+
+    assert_eq!(Vec::<Violation>::new(), violations);
+}
+
+#[test]
+fn too_much_work_on_average_is_a_violation() {
+    let config = ComplianceConfig {
+        reference_weeks: 2,
+        ..Default::default()
+    };
+    // 60 hours in week 3 and 40 hours in week 4 is 50 hours on average
+    let mut days: Vec<Day> = (15..=19).map(|date| day(date, &["07:00-19:00"])).collect();
+    days.extend((22..=26).map(|date| day(date, &["08:00-16:00"])));
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 22),
+        naive_date_time(2024, 1, 29, 12, 0),
+        &config,
+        &WorkWeekConfig::default(),
+    );
+
+    assert_eq!(
+        vec![Violation::AverageWeek {
+            first_week: iso_week(2024, 3),
+            last_week: iso_week(2024, 4),
+            average: Duration::hours(50),
+        }],
+        violations
+    );
+}
+
+#[test]
+fn weeks_start_on_the_configured_day() {
+    // Working every day from sunday to saturday, with the week starting on sunday
+    let days: Vec<Day> = (21..=27).map(|date| day(date, &["08:00-20:00"])).collect();
+    let workweek = WorkWeekConfig {
+        week_start: Weekday::Sun,
+        ..Default::default()
+    };
+
+    let violations = check(
+        &days,
+        naive_date(2024, 1, 21),
+        naive_date_time(2024, 1, 28, 12, 0),
+        &ComplianceConfig::default(),
+        &workweek,
+    );
+
+    assert_eq!(
+        vec![Violation::WeeklyRest {
+            week: iso_week(2024, 4),
+            longest_rest: Duration::hours(12),
+        }],
+        violations
+    );
+    assert_eq!(naive_date(2024, 1, 27), violations[0].date(&workweek));
+}
@@ -1,34 +1,104 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
-use serde::Deserialize;
+use chrono::{Datelike, Duration, IsoWeek, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::constants;
+use crate::locale::Locale;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default)]
     pub workweek: WorkWeekConfig,
     #[serde(default)]
     pub experimental_features: FeaturesConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub billing: BillingConfig,
+    #[serde(default)]
+    pub overtime: OvertimeConfig,
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    #[serde(default)]
+    pub flex: FlexConfig,
+    #[serde(default)]
+    pub supplements: Vec<SupplementWindow>,
+    #[serde(default)]
+    pub oncall: OnCallConfig,
+    /// Named profiles, such as for different employers, each with their own week files
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// The language of day headers in week files and of reports
+    #[serde(default)]
+    pub locale: Locale,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Config {
-            workweek: WorkWeekConfig::default(),
-            experimental_features: FeaturesConfig::default(),
-        }
+impl Config {
+    /// The work week of a profile, or of the default profile if `None`
+    pub fn workweek_for(&self, profile: Option<&str>) -> &WorkWeekConfig {
+        profile
+            .and_then(|profile| self.profiles.get(profile))
+            .and_then(|profile| profile.workweek.as_ref())
+            .unwrap_or(&self.workweek)
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct WorkWeekConfig {
     #[serde(default = "default_days_per_week")]
     pub days_per_week: u32,
     #[serde(default = "default_hours_per_day")]
     pub hours_per_day: u32,
+    /// The day that weeks start on
+    #[serde(
+        default = "default_week_start",
+        deserialize_with = "deserialize_weekday",
+        serialize_with = "serialize_weekday"
+    )]
+    pub week_start: Weekday,
+    /// The days that are worked. If not given, it's the first `days_per_week` days from
+    /// monday.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_weekdays",
+        serialize_with = "serialize_optional_weekdays",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub working_days: Option<Vec<Weekday>>,
+    /// When a forgotten shift is assumed to have ended, unless told otherwise
+    #[serde(
+        default = "default_end_time",
+        deserialize_with = "deserialize_time",
+        serialize_with = "serialize_time"
+    )]
+    pub default_end_time: NaiveTime,
+    /// Public holidays
+    #[serde(
+        default,
+        deserialize_with = "deserialize_dates",
+        serialize_with = "serialize_dates"
+    )]
+    pub holidays: Vec<NaiveDate>,
+    /// The home time zone, like `Europe/Stockholm`, that times are in unless a day has its
+    /// own UTC offset. Without it, daylight saving time is not taken into account.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_time_zone",
+        serialize_with = "serialize_optional_time_zone",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_zone: Option<Tz>,
 }
 
 impl Default for WorkWeekConfig {
@@ -36,10 +106,47 @@ impl Default for WorkWeekConfig {
         WorkWeekConfig {
             days_per_week: default_days_per_week(),
             hours_per_day: default_hours_per_day(),
+            week_start: default_week_start(),
+            working_days: None,
+            default_end_time: default_end_time(),
+            holidays: vec![],
+            time_zone: None,
         }
     }
 }
 
+impl WorkWeekConfig {
+    /// Whether work is expected on a weekday
+    pub fn is_working_day(&self, weekday: Weekday) -> bool {
+        match &self.working_days {
+            Some(working_days) => working_days.contains(&weekday),
+            None => weekday.num_days_from_monday() < self.days_per_week,
+        }
+    }
+
+    /// The week that a date is tracked in. Weeks are named after the ISO week of their monday,
+    /// even if they start on another day.
+    pub fn week_of(&self, date: NaiveDate) -> IsoWeek {
+        self.filing_date(date).iso_week()
+    }
+
+    /// The first day of a week
+    pub fn first_day(&self, week: IsoWeek) -> NaiveDate {
+        NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon).unwrap()
+            - Duration::days(self.days_from_start_to_monday())
+    }
+
+    /// A date moved so that the week it is in runs from monday to sunday, which is what the
+    /// names of the week files are based on
+    pub fn filing_date(&self, date: NaiveDate) -> NaiveDate {
+        date + Duration::days(self.days_from_start_to_monday())
+    }
+
+    fn days_from_start_to_monday(&self) -> i64 {
+        ((7 - self.week_start.num_days_from_monday()) % 7).into()
+    }
+}
+
 fn default_days_per_week() -> u32 {
     constants::DEFAULT_WORK_DAYS_PER_WEEK
 }
@@ -48,13 +155,491 @@ fn default_hours_per_day() -> u32 {
     constants::DEFAULT_WORK_HOURS_PER_DAY
 }
 
-#[derive(Debug, Deserialize, Default)]
+fn default_week_start() -> Weekday {
+    Weekday::Mon
+}
+
+fn default_end_time() -> NaiveTime {
+    NaiveTime::parse_from_str(constants::DEFAULT_END_TIME, "%H:%M").unwrap()
+}
+
+/// Times are written as `HH:MM`
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&string, "%H:%M").map_err(|_| {
+        serde::de::Error::custom(format!("invalid time \"{}\", expected HH:MM", string))
+    })
+}
+
+/// Dates are written as `YYYY-MM-DD`
+fn deserialize_dates<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<NaiveDate>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|string| {
+            NaiveDate::parse_from_str(string, "%Y-%m-%d").map_err(|_| {
+                serde::de::Error::custom(format!(
+                    "invalid date \"{}\", expected YYYY-MM-DD",
+                    string
+                ))
+            })
+        })
+        .collect()
+}
+
+static DURATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<sign>-)?\s*(?:(?P<hours>[0-9]+)\s*h)?\s*(?:(?P<minutes>[0-9]+)\s*m)?$")
+        .unwrap()
+});
+
+/// Durations are written like `10h`, `45m` or `9h 30m`, possibly negative like `-40h`
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    DURATION_REGEX
+        .captures(string.trim())
+        .filter(|captures| captures.name("hours").is_some() || captures.name("minutes").is_some())
+        .and_then(|captures| {
+            let number = |name| {
+                captures
+                    .name(name)
+                    .map_or(Ok(0), |m| m.as_str().parse::<i64>())
+            };
+            let sign = if captures.name("sign").is_some() {
+                -1
+            } else {
+                1
+            };
+            Duration::try_minutes(sign * (number("hours").ok()? * 60 + number("minutes").ok()?))
+        })
+        .ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid duration \"{}\", expected something like 10h or 9h 30m",
+                string
+            ))
+        })
+}
+
+fn deserialize_optional_time_zone<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Tz>, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    string
+        .parse::<Tz>()
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("unknown time zone \"{}\"", string)))
+}
+
+fn deserialize_optional_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveTime>, D::Error> {
+    deserialize_time(deserializer).map(Some)
+}
+
+fn deserialize_optional_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserialize_duration(deserializer).map(Some)
+}
+
+// Settings are written the way they are read, so that the defaults can be shown as they
+// would be written in the config file
+
+fn serialize_time<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.format("%H:%M").to_string())
+}
+
+fn serialize_optional_time<S: Serializer>(
+    time: &Option<NaiveTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serialize_time(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_dates<S: Serializer>(dates: &[NaiveDate], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(dates.iter().map(|date| date.format("%Y-%m-%d").to_string()))
+}
+
+fn serialize_duration<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sign = if *duration < Duration::zero() {
+        "-"
+    } else {
+        ""
+    };
+    let minutes = duration.num_minutes().abs();
+    serializer.serialize_str(&match (minutes / 60, minutes % 60) {
+        (hours, 0) => format!("{}{}h", sign, hours),
+        (0, minutes) => format!("{}{}m", sign, minutes),
+        (hours, minutes) => format!("{}{}h {}m", sign, hours, minutes),
+    })
+}
+
+fn serialize_optional_duration<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_duration(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_optional_time_zone<S: Serializer>(
+    zone: &Option<Tz>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match zone {
+        Some(zone) => serializer.serialize_str(zone.name()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_weekday<S: Serializer>(weekday: &Weekday, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(Locale::English.weekday_name(*weekday))
+}
+
+fn serialize_optional_weekdays<S: Serializer>(
+    weekdays: &Option<Vec<Weekday>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match weekdays {
+        Some(weekdays) => serializer.collect_seq(
+            weekdays
+                .iter()
+                .map(|weekday| Locale::English.weekday_name(*weekday)),
+        ),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 pub struct FeaturesConfig {
     #[serde(default)]
     pub auto_transfer_balance: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Keep the history of the week files in a local git repository
+    #[serde(default)]
+    pub git: bool,
+    /// Where week files and other data are kept, instead of the default location
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Limits on how long a shift that is never stopped keeps counting
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// The longest a shift may be
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_shift: Option<Duration>,
+    /// The time of day at which shifts stop at the latest
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_time",
+        serialize_with = "serialize_optional_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auto_stop_at: Option<NaiveTime>,
+}
+
+/// Shell commands to run when tracking starts or stops, or a report is shown. If a `pre_`
+/// hook fails, the action is not taken.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    pub pre_start: Option<String>,
+    pub on_start: Option<String>,
+    pub pre_stop: Option<String>,
+    pub on_stop: Option<String>,
+    pub on_report: Option<String>,
+}
+
+/// How billed time is rounded. The week files and the balance always use exact times.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BillingConfig {
+    #[serde(default)]
+    pub rounding: Rounding,
+    #[serde(
+        default = "default_billing_granularity",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub granularity: Duration,
+    /// What is rounded: each shift, the total of each day, or the total of each project on
+    /// each day
+    #[serde(default)]
+    pub per: RoundingScope,
+}
+
+impl Default for BillingConfig {
+    fn default() -> Self {
+        BillingConfig {
+            rounding: Rounding::default(),
+            granularity: default_billing_granularity(),
+            per: RoundingScope::default(),
+        }
+    }
+}
+
+fn default_billing_granularity() -> Duration {
+    Duration::try_minutes(constants::DEFAULT_BILLING_GRANULARITY_MINUTES).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    #[default]
+    Nearest,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingScope {
+    #[default]
+    Shift,
+    Day,
+    ProjectDay,
+}
+
+/// When worked time counts as overtime. Overtime is moved from the balance to a separate
+/// overtime bank, multiplied by the multiplier that applies.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OvertimeConfig {
+    /// Time worked beyond this on a day is overtime
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub daily_threshold: Option<Duration>,
+    /// Time worked beyond this in a week, not already overtime, is overtime
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub weekly_threshold: Option<Duration>,
+    #[serde(default = "default_overtime_multiplier")]
+    pub multiplier: f64,
+    /// If set, all time worked on days that aren't working days is overtime with this
+    /// multiplier
+    pub weekend_multiplier: Option<f64>,
+    /// If set, all time worked on holidays is overtime with this multiplier
+    pub holiday_multiplier: Option<f64>,
+}
+
+impl Default for OvertimeConfig {
+    fn default() -> Self {
+        OvertimeConfig {
+            daily_threshold: None,
+            weekly_threshold: None,
+            multiplier: default_overtime_multiplier(),
+            weekend_multiplier: None,
+            holiday_multiplier: None,
+        }
+    }
+}
+
+fn default_overtime_multiplier() -> f64 {
+    constants::DEFAULT_OVERTIME_MULTIPLIER
+}
+
+/// Working-time rules to check the tracked time against
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComplianceConfig {
+    /// Shortest rest between two days of work
+    #[serde(
+        default = "default_daily_rest",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub daily_rest: Duration,
+    /// Shortest uninterrupted rest during a week
+    #[serde(
+        default = "default_weekly_rest",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub weekly_rest: Duration,
+    /// Most time worked per week, on average over the reference period
+    #[serde(
+        default = "default_max_average_week",
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub max_average_week: Duration,
+    /// Number of weeks in the reference period
+    #[serde(default = "default_reference_weeks")]
+    pub reference_weeks: u32,
+    /// Also warn about violations in `tracker report`
+    #[serde(default)]
+    pub in_report: bool,
+}
+
+impl Default for ComplianceConfig {
+    fn default() -> Self {
+        ComplianceConfig {
+            daily_rest: default_daily_rest(),
+            weekly_rest: default_weekly_rest(),
+            max_average_week: default_max_average_week(),
+            reference_weeks: default_reference_weeks(),
+            in_report: false,
+        }
+    }
+}
+
+fn default_daily_rest() -> Duration {
+    Duration::try_hours(constants::DEFAULT_DAILY_REST_HOURS).unwrap()
+}
+
+fn default_weekly_rest() -> Duration {
+    Duration::try_hours(constants::DEFAULT_WEEKLY_REST_HOURS).unwrap()
+}
+
+fn default_max_average_week() -> Duration {
+    Duration::try_hours(constants::DEFAULT_MAX_AVERAGE_WEEK_HOURS).unwrap()
+}
+
+fn default_reference_weeks() -> u32 {
+    constants::DEFAULT_REFERENCE_WEEKS
+}
+
+/// Rules for the balance when it is carried over to a new week file
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FlexConfig {
+    /// The highest balance that can be carried over
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ceiling: Option<Duration>,
+    /// The lowest balance that can be carried over
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_duration",
+        serialize_with = "serialize_optional_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub floor: Option<Duration>,
+    /// At the start of each such period, positive balance above `max_carry_over` is forfeited
+    pub period: Option<FlexPeriod>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_duration",
+        serialize_with = "serialize_duration"
+    )]
+    pub max_carry_over: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FlexPeriod {
+    Month,
+    Quarter,
+    Year,
+}
+
+/// A time window in which work gives a supplement of some category, such as evenings or
+/// weekends. When windows overlap, the first one applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SupplementWindow {
+    pub category: String,
+    /// The days the window starts on, all days if not given
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_weekdays",
+        serialize_with = "serialize_optional_weekdays",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub days: Option<Vec<Weekday>>,
+    /// Start of the window, the start of the day if not given
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_time",
+        serialize_with = "serialize_optional_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from: Option<NaiveTime>,
+    /// End of the window, the end of the day if not given. If it is before the start, the
+    /// window ends the next day.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_time",
+        serialize_with = "serialize_optional_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to: Option<NaiveTime>,
+    /// Only apply on holidays
+    #[serde(default)]
+    pub holidays: bool,
+}
+
+fn deserialize_weekday<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Weekday, D::Error> {
+    let string = String::deserialize(deserializer)?;
+    string
+        .parse::<Weekday>()
+        .map_err(|_| serde::de::Error::custom(format!("invalid weekday \"{}\"", string)))
+}
+
+/// Weekdays are written like `mon` or `monday`
+fn deserialize_optional_weekdays<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<Weekday>>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|string| {
+            string
+                .parse::<Weekday>()
+                .map_err(|_| serde::de::Error::custom(format!("invalid weekday \"{}\"", string)))
+        })
+        .collect::<Result<Vec<Weekday>, D::Error>>()
+        .map(Some)
+}
+
+/// Settings that differ for a profile. Anything not given is the same as for the default
+/// profile.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub workweek: Option<WorkWeekConfig>,
+}
+
+/// How time on call is compensated
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OnCallConfig {
+    /// The fraction of standby time that is credited, e.g. 0.25 for a quarter
+    #[serde(default)]
+    pub compensation_ratio: f64,
+}
+
 #[derive(Debug)]
 pub enum ConfigError {
     OpenFile(PathBuf, std::io::Error),
@@ -62,12 +647,16 @@ pub enum ConfigError {
 }
 
 pub fn read_config_from_str(str: &str) -> Result<Config, toml::de::Error> {
-    toml::from_str(&str)
+    toml::from_str(str)
 }
 
+/// Reads the config file, with all settings at their defaults if there is no such file
 pub fn read_config_from_path(path: &Path) -> Result<Config, ConfigError> {
-    let contents =
-        std::fs::read_to_string(&path).map_err(|e| ConfigError::OpenFile(path.to_path_buf(), e))?;
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(ConfigError::OpenFile(path.to_path_buf(), e)),
+    };
     read_config_from_str(&contents).map_err(|e| ConfigError::InvalidFile(path.to_path_buf(), e))
 }
 
@@ -90,6 +679,34 @@ mod tests {
         assert_eq!(config.workweek.hours_per_day, 5);
     }
 
+    #[test]
+    fn reads_default_end_time() {
+        let config = read_config_from_str(
+            r#"
+            [workweek]
+            default_end_time = "16:30"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.workweek.default_end_time,
+            NaiveTime::from_hms_opt(16, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_time() {
+        let result = read_config_from_str(
+            r#"
+            [workweek]
+            default_end_time = "half past four"
+            "#,
+        );
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn uses_defaults() {
         let config = read_config_from_str(
@@ -109,5 +726,326 @@ mod tests {
 
         assert_eq!(config.workweek.days_per_week, 5);
         assert_eq!(config.workweek.hours_per_day, 8);
+        assert!(!config.storage.git);
+        assert_eq!(config.limits.max_shift, None);
+        assert_eq!(config.limits.auto_stop_at, None);
+    }
+
+    #[test]
+    fn reads_limits() {
+        let config = read_config_from_str(
+            r#"
+            [limits]
+            max_shift = "9h 30m"
+            auto_stop_at = "18:30"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.limits.max_shift, Duration::try_minutes(570));
+        assert_eq!(
+            config.limits.auto_stop_at,
+            Some(NaiveTime::from_hms_opt(18, 30, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn reads_hooks() {
+        let config = read_config_from_str(
+            r#"
+            [hooks]
+            pre_start = "test -f ~/.working-allowed"
+            on_stop = "notify-send stopped"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.hooks.pre_start.as_deref(),
+            Some("test -f ~/.working-allowed")
+        );
+        assert_eq!(config.hooks.on_start, None);
+        assert_eq!(config.hooks.on_stop.as_deref(), Some("notify-send stopped"));
+    }
+
+    #[test]
+    fn reads_billing() {
+        let config = read_config_from_str(
+            r#"
+            [billing]
+            rounding = "up"
+            granularity = "15m"
+            per = "project-day"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.billing.rounding, Rounding::Up);
+        assert_eq!(config.billing.granularity, Duration::minutes(15));
+        assert_eq!(config.billing.per, RoundingScope::ProjectDay);
+    }
+
+    #[test]
+    fn reads_overtime() {
+        let config = read_config_from_str(
+            r#"
+            [workweek]
+            holidays = ["2024-12-25", "2024-12-26"]
+
+            [overtime]
+            daily_threshold = "9h"
+            weekly_threshold = "40h"
+            holiday_multiplier = 2.0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.workweek.holidays,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 12, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 26).unwrap()
+            ]
+        );
+        assert_eq!(config.overtime.daily_threshold, Duration::try_hours(9));
+        assert_eq!(config.overtime.weekly_threshold, Duration::try_hours(40));
+        assert_eq!(config.overtime.multiplier, 1.5);
+        assert_eq!(config.overtime.weekend_multiplier, None);
+        assert_eq!(config.overtime.holiday_multiplier, Some(2.0));
+    }
+
+    #[test]
+    fn reads_compliance() {
+        let config = read_config_from_str(
+            r#"
+            [compliance]
+            weekly_rest = "35h"
+            reference_weeks = 4
+            in_report = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.compliance.daily_rest, Duration::hours(11));
+        assert_eq!(config.compliance.weekly_rest, Duration::hours(35));
+        assert_eq!(config.compliance.max_average_week, Duration::hours(48));
+        assert_eq!(config.compliance.reference_weeks, 4);
+        assert!(config.compliance.in_report);
+    }
+
+    #[test]
+    fn reads_oncall() {
+        let config = read_config_from_str(
+            r#"
+            [oncall]
+            compensation_ratio = 0.25
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.oncall.compensation_ratio, 0.25);
+    }
+
+    #[test]
+    fn missing_config_file_means_defaults() {
+        let config = read_config_from_path(Path::new("/does/not/exist/config.toml")).unwrap();
+
+        assert_eq!(config.workweek.hours_per_day, 8);
+        assert_eq!(config.storage.data_dir, None);
+    }
+
+    #[test]
+    fn reads_data_dir() {
+        let config = read_config_from_str(
+            r#"
+            [storage]
+            data_dir = "/home/me/Sync/tracker"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.storage.data_dir,
+            Some(PathBuf::from("/home/me/Sync/tracker"))
+        );
+    }
+
+    #[test]
+    fn reads_locale() {
+        let config = read_config_from_str(
+            r#"
+            locale = "sv"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.locale, Locale::Swedish);
+    }
+
+    #[test]
+    fn reads_week_start_and_working_days() {
+        let config = read_config_from_str(
+            r#"
+            [workweek]
+            week_start = "sunday"
+            working_days = ["tue", "wed", "thu", "fri", "sat"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.workweek.week_start, Weekday::Sun);
+        assert!(!config.workweek.is_working_day(Weekday::Mon));
+        assert!(config.workweek.is_working_day(Weekday::Sat));
+    }
+
+    #[test]
+    fn weeks_starting_on_sunday_are_named_after_their_monday() {
+        let workweek = WorkWeekConfig {
+            week_start: Weekday::Sun,
+            ..Default::default()
+        };
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 21).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2024, 1, 27).unwrap();
+
+        assert_eq!(workweek.week_of(sunday), saturday.iso_week());
+        assert_eq!(workweek.week_of(saturday), saturday.iso_week());
+        assert_eq!(workweek.first_day(saturday.iso_week()), sunday);
+        assert_eq!(
+            workweek.week_of(saturday + Duration::days(1)),
+            (saturday + Duration::days(7)).iso_week()
+        );
+    }
+
+    #[test]
+    fn weeks_starting_on_monday_are_iso_weeks() {
+        let workweek = WorkWeekConfig::default();
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 21).unwrap();
+
+        assert_eq!(workweek.week_of(sunday), sunday.iso_week());
+        assert_eq!(workweek.filing_date(sunday), sunday);
+        assert_eq!(
+            workweek.first_day(sunday.iso_week()),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn reads_time_zone() {
+        let config = read_config_from_str(
+            r#"
+            [workweek]
+            time_zone = "Europe/Stockholm"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.workweek.time_zone,
+            Some(chrono_tz::Europe::Stockholm)
+        );
+        assert!(read_config_from_str("[workweek]\ntime_zone = \"Mars/Olympus\"").is_err());
+    }
+
+    #[test]
+    fn reads_profiles() {
+        let config = read_config_from_str(
+            r#"
+            [workweek]
+            hours_per_day = 8
+
+            [profiles.consulting.workweek]
+            days_per_week = 2
+            hours_per_day = 4
+
+            [profiles.side-project]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.workweek_for(None).hours_per_day, 8);
+        assert_eq!(config.workweek_for(Some("consulting")).days_per_week, 2);
+        assert_eq!(config.workweek_for(Some("consulting")).hours_per_day, 4);
+        assert_eq!(config.workweek_for(Some("side-project")).hours_per_day, 8);
+        assert_eq!(
+            config.profiles.keys().collect::<Vec<_>>(),
+            vec!["consulting", "side-project"]
+        );
+    }
+
+    #[test]
+    fn reads_flex() {
+        let config = read_config_from_str(
+            r#"
+            [flex]
+            ceiling = "40h"
+            floor = "-40h"
+            period = "quarter"
+            max_carry_over = "10h"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.flex.ceiling, Duration::try_hours(40));
+        assert_eq!(config.flex.floor, Duration::try_hours(-40));
+        assert_eq!(config.flex.period, Some(FlexPeriod::Quarter));
+        assert_eq!(config.flex.max_carry_over, Duration::hours(10));
+    }
+
+    #[test]
+    fn reads_supplements() {
+        let config = read_config_from_str(
+            r#"
+            [[supplements]]
+            category = "night"
+            from = "22:00"
+            to = "06:00"
+
+            [[supplements]]
+            category = "weekend"
+            days = ["sat", "sunday"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.supplements.len(), 2);
+        assert_eq!(config.supplements[0].category, "night");
+        assert_eq!(config.supplements[0].days, None);
+        assert_eq!(
+            config.supplements[0].to,
+            Some(NaiveTime::from_hms_opt(6, 0, 0).unwrap())
+        );
+        assert_eq!(
+            config.supplements[1].days,
+            Some(vec![Weekday::Sat, Weekday::Sun])
+        );
+        assert_eq!(config.supplements[1].from, None);
+    }
+
+    #[test]
+    fn reads_durations_in_hours_or_minutes() {
+        let config = read_config_from_str("[limits]\nmax_shift = \"10h\"").unwrap();
+        assert_eq!(config.limits.max_shift, Duration::try_hours(10));
+
+        let config = read_config_from_str("[limits]\nmax_shift = \"45m\"").unwrap();
+        assert_eq!(config.limits.max_shift, Duration::try_minutes(45));
+    }
+
+    #[test]
+    fn rejects_invalid_duration() {
+        assert!(read_config_from_str("[limits]\nmax_shift = \"\"").is_err());
+        assert!(read_config_from_str("[limits]\nmax_shift = \"ten hours\"").is_err());
+    }
+
+    #[test]
+    fn reads_storage() {
+        let config = read_config_from_str(
+            r"
+            [storage]
+            git = true
+            ",
+        )
+        .unwrap();
+
+        assert!(config.storage.git);
     }
 }
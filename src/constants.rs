@@ -1,2 +1,10 @@
 pub const DEFAULT_WORK_HOURS_PER_DAY: u32 = 8;
 pub const DEFAULT_WORK_DAYS_PER_WEEK: u32 = 5;
+pub const DEFAULT_END_TIME: &str = "17:00";
+pub const DEFAULT_BILLING_GRANULARITY_MINUTES: i64 = 1;
+pub const DEFAULT_OVERTIME_MULTIPLIER: f64 = 1.5;
+pub const DEFAULT_DAILY_REST_HOURS: i64 = 11;
+pub const DEFAULT_WEEKLY_REST_HOURS: i64 = 36;
+pub const DEFAULT_MAX_AVERAGE_WEEK_HOURS: i64 = 48;
+// About four months
+pub const DEFAULT_REFERENCE_WEEKS: u32 = 17;
@@ -1,8 +1,11 @@
 use crate::document::Line::{
-    Blank, ClosedShift, Comment, DayHeader, DurationShift, OpenShift, SpecialDay, SpecialShift,
+    Blank, ClosedShift, Comment, DayHeader, DurationShift, OnCall, OpenShift, SpecialDay,
+    SpecialShift, UtcOffset,
 };
-use chrono::{Datelike, Duration, IsoWeek, NaiveDate, NaiveTime, TimeDelta};
+use crate::locale::Locale;
+use chrono::{Datelike, Duration, FixedOffset, IsoWeek, NaiveDate, NaiveTime, TimeDelta, Weekday};
 use regex::{Captures, Regex};
+use std::fmt;
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum Line {
@@ -31,6 +34,16 @@ pub enum Line {
         start_time: NaiveTime,
         stop_time: NaiveTime,
     },
+    /// A period on call, which is not work in itself. A stop time before the start time
+    /// means that it ended the next day.
+    OnCall {
+        start_time: NaiveTime,
+        stop_time: Option<NaiveTime>,
+    },
+    /// The UTC offset that the times of a day are in, when it is not the home time zone
+    UtcOffset {
+        offset: FixedOffset,
+    },
     Blank,
 }
 
@@ -40,40 +53,78 @@ impl Line {
             || matches!(self, ClosedShift { .. })
             || matches!(self, SpecialShift { .. })
     }
+
+    /// The start time, if this is a shift
+    pub fn start_time(&self) -> Option<NaiveTime> {
+        match self {
+            OpenShift { start_time }
+            | ClosedShift { start_time, .. }
+            | SpecialShift { start_time, .. } => Some(*start_time),
+            _ => None,
+        }
+    }
+
+    /// The stop time, if this is a shift that has been stopped
+    pub fn stop_time(&self) -> Option<NaiveTime> {
+        match self {
+            ClosedShift { stop_time, .. } | SpecialShift { stop_time, .. } => Some(*stop_time),
+            _ => None,
+        }
+    }
 }
 
-impl ToString for Line {
-    fn to_string(&self) -> String {
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Comment { text } => format!("# {}", text),
-            DayHeader { date } => format!("[{} {}]", "foobar", date),
-            OpenShift { start_time } => format!("* {}-{}", start_time.format("%H:%M"), ""),
+            Comment { text } => write!(f, "# {}", text),
+            DayHeader { date } => write!(
+                f,
+                "[{} {}]",
+                Locale::English.weekday_name(date.weekday()),
+                date
+            ),
+            OpenShift { start_time } => write!(f, "* {}-", start_time.format("%H:%M")),
             ClosedShift {
                 start_time,
                 stop_time,
-            } => format!(
+            } => write!(
+                f,
                 "* {}-{}",
                 start_time.format("%H:%M"),
                 stop_time.format("%H:%M")
             ),
-            DurationShift { text, duration } => format!(
+            DurationShift { text, duration } => write!(
+                f,
                 "* {} {}h {}m",
                 text,
                 duration.num_hours(),
                 duration.num_minutes() - duration.num_hours() * 60
             ),
-            SpecialDay { text } => format!("* {}", text),
+            SpecialDay { text } => write!(f, "* {}", text),
             SpecialShift {
                 text,
                 start_time,
                 stop_time,
-            } => format!(
+            } => write!(
+                f,
                 "* {} {}-{}",
                 text,
                 start_time.format("%H:%M"),
                 stop_time.format("%H:%M")
             ),
-            Blank => String::from(""),
+            OnCall {
+                start_time,
+                stop_time,
+            } => write!(
+                f,
+                "* oncall {}-{}",
+                start_time.format("%H:%M"),
+                stop_time
+                    .map(|stop_time| stop_time.format("%H:%M").to_string())
+                    .unwrap_or_default()
+            ),
+            UtcOffset { offset } => write!(f, "* offset {}", offset),
+            Blank => Ok(()),
         }
     }
 }
@@ -86,10 +137,9 @@ pub struct Day {
 
 impl Day {
     pub fn has_open_shift(&self) -> bool {
-        return self
-            .lines
+        self.lines
             .iter()
-            .any(|line| matches!(line, OpenShift { .. }));
+            .any(|line| matches!(line, OpenShift { .. }))
     }
 
     pub fn adding_shift(&self, line: Line) -> Self {
@@ -110,6 +160,90 @@ impl Day {
         }
     }
 
+    /// Returns the same day but with a shift inserted among the other shifts, ordered by start time
+    pub fn inserting_shift(&self, line: Line) -> Self {
+        let start_time = line.start_time();
+        let index = self
+            .lines
+            .iter()
+            .position(|existing| existing.is_shift() && existing.start_time() > start_time)
+            .or_else(|| {
+                self.lines
+                    .iter()
+                    .rposition(|existing| existing.is_shift())
+                    .map(|index| index + 1)
+            })
+            .unwrap_or(0);
+        let mut lines = self.lines.clone();
+        lines.insert(index, line);
+        Day {
+            date: self.date,
+            lines,
+        }
+    }
+
+    /// Returns the same day but without the shift starting at a certain time, or `None` if
+    /// there is no such shift
+    pub fn removing_shift(&self, start_time: NaiveTime) -> Option<Self> {
+        let index = self
+            .lines
+            .iter()
+            .position(|line| line.start_time() == Some(start_time))?;
+        let mut lines = self.lines.clone();
+        lines.remove(index);
+        Some(Day {
+            date: self.date,
+            lines,
+        })
+    }
+
+    /// Comment lines directly following a line are considered notes on that line
+    pub fn note_for_line(&self, index: usize) -> String {
+        self.lines[index + 1..]
+            .iter()
+            .map_while(|line| match line {
+                Comment { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Returns the same day but with a comment right after the shift starting at a certain time
+    pub fn annotating_shift(&self, start_time: NaiveTime, text: &str) -> Self {
+        let mut lines = self.lines.clone();
+        if let Some(index) = lines
+            .iter()
+            .position(|line| line.start_time() == Some(start_time))
+        {
+            lines.insert(
+                index + 1,
+                Comment {
+                    text: String::from(text),
+                },
+            );
+        }
+        Day {
+            date: self.date,
+            lines,
+        }
+    }
+
+    /// Find the shift starting at a certain time
+    pub fn get_shift(&self, start_time: NaiveTime) -> Option<&Line> {
+        self.lines
+            .iter()
+            .find(|line| line.start_time() == Some(start_time))
+    }
+
+    /// The shift starting last
+    pub fn last_shift(&self) -> Option<&Line> {
+        self.lines
+            .iter()
+            .filter(|line| line.is_shift())
+            .max_by_key(|line| line.start_time())
+    }
+
     pub fn closing_shift(&self, closing_time: NaiveTime) -> Self {
         let open_shift_count = self
             .lines
@@ -140,33 +274,113 @@ impl Day {
         }
     }
 
+    /// Returns the same day but with an on-call period after the shifts and other on-call
+    /// periods
+    pub fn adding_on_call(&self, line: Line) -> Self {
+        let index = self
+            .lines
+            .iter()
+            .rposition(|line| line.is_shift() || matches!(line, OnCall { .. }))
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let mut lines = self.lines.clone();
+        lines.insert(index, line);
+        Day {
+            date: self.date,
+            lines,
+        }
+    }
+
+    /// The start time of the on-call period that has not been stopped, if there is one
+    pub fn open_on_call(&self) -> Option<NaiveTime> {
+        self.lines.iter().find_map(|line| match line {
+            OnCall {
+                start_time,
+                stop_time: None,
+            } => Some(*start_time),
+            _ => None,
+        })
+    }
+
+    pub fn closing_on_call(&self, closing_time: NaiveTime) -> Self {
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .map(|line| match line {
+                OnCall {
+                    start_time,
+                    stop_time: None,
+                } => OnCall {
+                    start_time: *start_time,
+                    stop_time: Some(closing_time),
+                },
+                _ => line.clone(),
+            })
+            .collect();
+        Day {
+            date: self.date,
+            lines,
+        }
+    }
+
+    /// The UTC offset that the times of this day are in, if it is not the home time zone
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        self.lines.iter().find_map(|line| match line {
+            UtcOffset { offset } => Some(*offset),
+            _ => None,
+        })
+    }
+
+    /// Returns the same day but with its times in a UTC offset, unless it already has one.
+    /// The offset goes last, before any blank lines.
+    pub fn with_utc_offset(&self, offset: FixedOffset) -> Self {
+        let mut lines = self.lines.clone();
+        if self.utc_offset().is_none() {
+            let index = lines
+                .iter()
+                .rposition(|line| *line != Blank)
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            lines.insert(index, UtcOffset { offset });
+        }
+        Day {
+            date: self.date,
+            lines,
+        }
+    }
+
     pub fn create(date: NaiveDate, lines: Vec<Line>) -> Self {
         Day { date, lines }
     }
 }
 
-fn format_weekday(date: NaiveDate) -> String {
-    match date.weekday() {
-        chrono::Weekday::Mon => String::from("monday"),
-        chrono::Weekday::Tue => String::from("tuesday"),
-        chrono::Weekday::Wed => String::from("wednesday"),
-        chrono::Weekday::Thu => String::from("thursday"),
-        chrono::Weekday::Fri => String::from("friday"),
-        chrono::Weekday::Sat => String::from("saturday"),
-        chrono::Weekday::Sun => String::from("sunday"),
+impl Day {
+    /// The day as written to a week file, with its header in a certain locale
+    pub fn to_string_in(&self, locale: Locale) -> String {
+        let mut string = String::new();
+        self.write_in(&mut string, locale)
+            .expect("Writing to a string can not fail");
+        string
     }
-}
 
-impl ToString for Day {
-    fn to_string(&self) -> String {
-        let mut string = String::new();
-        string.push_str(&format!("[{} {}]", format_weekday(self.date), self.date));
-        string.push('\n');
+    /// Writes the day with its header in a certain locale
+    fn write_in(&self, f: &mut impl fmt::Write, locale: Locale) -> fmt::Result {
+        writeln!(
+            f,
+            "[{} {}]",
+            locale.weekday_name(self.date.weekday()),
+            self.date
+        )?;
         for line in &self.lines {
-            string.push_str(&line.to_string());
-            string.push('\n');
+            writeln!(f, "{}", line)?;
         }
-        string
+        Ok(())
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_in(f, Locale::English)
     }
 }
 
@@ -191,12 +405,41 @@ impl Document {
     }
 
     pub fn has_open_shift(&self) -> bool {
-        return self.days.iter().any(|day| day.has_open_shift());
+        self.days.iter().any(|day| day.has_open_shift())
+    }
+
+    /// The date and start time of the open shift, if there is one
+    pub fn open_shift(&self) -> Option<(NaiveDate, NaiveTime)> {
+        self.days.iter().find_map(|day| {
+            day.lines.iter().find_map(|line| match line {
+                OpenShift { start_time } => Some((day.date, *start_time)),
+                _ => None,
+            })
+        })
+    }
+
+    /// The date and start time of the on-call period that has not been stopped, if there is one
+    pub fn open_on_call(&self) -> Option<(NaiveDate, NaiveTime)> {
+        self.days
+            .iter()
+            .find_map(|day| day.open_on_call().map(|start_time| (day.date, start_time)))
+    }
+
+    /// The line number, counting from 1, of the header of each day as written
+    pub fn day_header_line_numbers(&self) -> Vec<usize> {
+        self.days
+            .iter()
+            .scan(self.preamble.len() + 1, |line_number, day| {
+                let header = *line_number;
+                *line_number += day.lines.len() + 1;
+                Some(header)
+            })
+            .collect()
     }
 
     /// Find a day
     pub fn get_day(&self, date: NaiveDate) -> Option<&Day> {
-        return self.days.iter().find(|d| d.date == date);
+        self.days.iter().find(|d| d.date == date)
     }
 
     /// Returns the same document but with a certain day replaced
@@ -213,6 +456,55 @@ impl Document {
         }
     }
 
+    /// Returns the same document but without a certain day. If it was the last day, the blank
+    /// line that `inserting_day` added before it is removed as well.
+    pub fn removing_day(&self, date: NaiveDate) -> Self {
+        let mut days: Vec<Day> = self
+            .days
+            .iter()
+            .filter(|d| d.date != date)
+            .cloned()
+            .collect();
+        let was_last = self.days.last().is_some_and(|d| d.date == date);
+        if was_last
+            && let Some(last_day) = days.last_mut()
+            && last_day.lines.last() == Some(&Blank)
+        {
+            last_day.lines.pop();
+        }
+        Document {
+            week: self.week,
+            preamble: self.preamble.clone(),
+            days,
+        }
+    }
+
+    /// Returns the same document but with a shift added to a certain date, inserting the
+    /// day if needed
+    pub fn inserting_shift(&self, date: NaiveDate, line: Line) -> Self {
+        match self.get_day(date) {
+            Some(day) => self.replacing_day(date, day.inserting_shift(line)),
+            None => self.inserting_day(Day::create(date, vec![line])),
+        }
+    }
+
+    /// Returns the same document but with an on-call period added to a certain date,
+    /// inserting the day if needed
+    pub fn inserting_on_call(&self, date: NaiveDate, line: Line) -> Self {
+        match self.get_day(date) {
+            Some(day) => self.replacing_day(date, day.adding_on_call(line)),
+            None => self.inserting_day(Day::create(date, vec![line])),
+        }
+    }
+
+    /// The last day that has any shifts
+    pub fn last_day_with_shift(&self) -> Option<&Day> {
+        self.days
+            .iter()
+            .filter(|day| day.last_shift().is_some())
+            .max_by_key(|day| day.date)
+    }
+
     /// Returns the same document but with a certain day inserted in the right place.
     /// And with a blank line before it if needed.
     pub fn inserting_day(&self, day: Day) -> Self {
@@ -245,25 +537,59 @@ impl Document {
     }
 }
 
-impl ToString for Document {
-    fn to_string(&self) -> String {
+impl Document {
+    /// The document as written to a week file, with day headers in a certain locale
+    pub fn to_string_in(&self, locale: Locale) -> String {
         let mut string = String::new();
+        self.write_in(&mut string, locale)
+            .expect("Writing to a string can not fail");
+        string
+    }
+
+    fn write_in(&self, f: &mut impl fmt::Write, locale: Locale) -> fmt::Result {
         for line in &self.preamble {
-            string.push_str(&line.to_string());
-            string.push('\n');
+            writeln!(f, "{}", line)?;
         }
         for day in &self.days {
-            string.push_str(&day.to_string());
+            day.write_in(f, locale)?;
         }
-        string
+        Ok(())
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_in(f, Locale::English)
+    }
+}
+
+/// A line in a week file that could not be parsed
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParseError {
+    /// The line number, counting from 1
+    pub line_number: usize,
+    pub line: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} could not be parsed: {} ({})",
+            self.line_number, self.line, self.reason
+        )
     }
 }
 
 pub struct Parser {
+    locale: Locale,
     comment_regex: Regex,
     day_header_regex: Regex,
     open_shift_regex: Regex,
     closed_shift_regex: Regex,
+    on_call_regex: Regex,
+    utc_offset_regex: Regex,
     duration_shift_regex: Regex,
     special_shift_regex: Regex,
     special_day_regex: Regex,
@@ -278,17 +604,28 @@ fn get_u32(m: &Captures, name: &str) -> u32 {
     m.name(name).unwrap().as_str().parse::<u32>().unwrap()
 }
 
+/// A time of day from an hour and a minute, which the regexes only check are two digits
+fn get_time(m: &Captures, hour: &str, minute: &str) -> Result<NaiveTime, String> {
+    NaiveTime::from_hms_opt(get_u32(m, hour), get_u32(m, minute), 0)
+        .ok_or_else(|| String::from("not a valid time"))
+}
+
 fn get_i64(m: &Captures, name: &str) -> i64 {
     m.name(name).unwrap().as_str().parse::<i64>().unwrap()
 }
 
 impl Parser {
-    pub fn new() -> Self {
+    /// A parser that accepts day headers with weekday names in a certain locale, as well
+    /// as in English
+    pub fn new(locale: Locale) -> Self {
         Parser {
+            locale,
             comment_regex: Regex::new(r"^# (?P<text>.*)$").unwrap(),
-            day_header_regex: Regex::new(r"^\[[a-z]+\s+(?P<year>[0-9]{4})-(?P<month>[0-9]{2})-(?P<day>[0-9]{2})]\s*$").unwrap(),
+            day_header_regex: Regex::new(r"^\[(?P<weekday>\p{L}+)\s+(?P<year>[0-9]{4})-(?P<month>[0-9]{2})-(?P<day>[0-9]{2})]\s*$").unwrap(),
             open_shift_regex: Regex::new(r"^\* (?P<hour>[0-9]{2}):(?P<minute>[0-9]{2})-\s*$").unwrap(),
             closed_shift_regex: Regex::new(r"^\* (?P<startHour>[0-9]{2}):(?P<startMinute>[0-9]{2})-(?P<stopHour>[0-9]{2}):(?P<stopMinute>[0-9]{2})\s*$").unwrap(),
+            on_call_regex: Regex::new(r"^\* oncall (?P<startHour>[0-9]{2}):(?P<startMinute>[0-9]{2})-((?P<stopHour>[0-9]{2}):(?P<stopMinute>[0-9]{2}))?\s*$").unwrap(),
+            utc_offset_regex: Regex::new(r"^\* offset (?P<offset>[+-][0-9]{2}:[0-9]{2})\s*$").unwrap(),
             duration_shift_regex: Regex::new(r"^\* (?P<text>[A-Za-z]+)\s+(?P<hours>-?[0-9]+)\s*h\s+(?P<minutes>-?[0-9]+)\s*m\s*$").unwrap(),
             special_shift_regex: Regex::new(r"^\* (?P<text>[A-Za-z]+) (?P<startHour>[0-9]{2}):(?P<startMinute>[0-9]{2})-(?P<stopHour>[0-9]{2}):(?P<stopMinute>[0-9]{2})\s*$").unwrap(),
             special_day_regex: Regex::new(r"^\* (?P<text>[A-Za-z]+)\s*$").unwrap(),
@@ -296,16 +633,21 @@ impl Parser {
         }
     }
 
-    fn parse_line(&self, string: &str) -> Option<Line> {
+    fn parse_line(&self, string: &str) -> Result<Line, String> {
+        if let Some(day_header) = self.parse_day_header(string) {
+            return day_header;
+        }
         self.parse_comment(string)
-            .or_else(|| self.parse_day_header(string))
+            .map(Ok)
             .or_else(|| self.parse_open_shift(string))
             .or_else(|| self.parse_closed_shift(string))
+            .or_else(|| self.parse_on_call(string))
+            .or_else(|| self.parse_utc_offset(string).map(Ok))
             .or_else(|| self.parse_special_shift(string))
-            .or_else(|| self.parse_duration_shift(string))
-            .or_else(|| self.parse_special_day(string))
-            .or_else(|| self.parse_blank(string))
-            .or(None)
+            .or_else(|| self.parse_duration_shift(string).map(Ok))
+            .or_else(|| self.parse_special_day(string).map(Ok))
+            .or_else(|| self.parse_blank(string).map(Ok))
+            .unwrap_or_else(|| Err(String::from("not a known kind of line")))
     }
 
     fn parse_comment(&self, string: &str) -> Option<Line> {
@@ -314,41 +656,84 @@ impl Parser {
         })
     }
 
-    fn parse_day_header(&self, string: &str) -> Option<Line> {
-        self.day_header_regex.captures(string).map(|m| DayHeader {
-            date: NaiveDate::from_ymd_opt(
+    /// Parses a day header, checking that the weekday name is a name in the locale. Whether
+    /// it is the weekday of the date is left to `misnamed_day_headers`.
+    fn parse_day_header(&self, string: &str) -> Option<Result<Line, String>> {
+        self.parse_day_header_with_weekday(string)
+            .map(|header| header.map(|(date, _)| DayHeader { date }))
+    }
+
+    fn parse_day_header_with_weekday(
+        &self,
+        string: &str,
+    ) -> Option<Result<(NaiveDate, Weekday), String>> {
+        self.day_header_regex.captures(string).map(|m| {
+            let date = NaiveDate::from_ymd_opt(
                 get_i32(&m, "year"),
                 get_u32(&m, "month"),
                 get_u32(&m, "day"),
             )
-            .unwrap(),
+            .ok_or_else(|| String::from("not a valid date"))?;
+            let name = m.name("weekday").unwrap().as_str();
+            match self.locale.parse_weekday(name) {
+                Some(weekday) => Ok((date, weekday)),
+                None => Err(format!("{} is not the name of a weekday", name)),
+            }
         })
     }
 
-    fn parse_open_shift(&self, string: &str) -> Option<Line> {
-        self.open_shift_regex.captures(string).map(|m| OpenShift {
-            start_time: NaiveTime::from_hms_opt(get_u32(&m, "hour"), get_u32(&m, "minute"), 0)
-                .unwrap(),
+    /// The line numbers of the day headers that name another weekday than that of their
+    /// date, with what the weekday of the date is called
+    pub fn misnamed_day_headers(&self, string: &str) -> Vec<(usize, String)> {
+        string
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let (date, weekday) = self.parse_day_header_with_weekday(line)?.ok()?;
+                (weekday != date.weekday()).then(|| {
+                    (
+                        index + 1,
+                        format!("{} is a {}", date, self.locale.weekday_name(date.weekday())),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn parse_open_shift(&self, string: &str) -> Option<Result<Line, String>> {
+        self.open_shift_regex.captures(string).map(|m| {
+            Ok(OpenShift {
+                start_time: get_time(&m, "hour", "minute")?,
+            })
         })
     }
 
-    fn parse_closed_shift(&self, string: &str) -> Option<Line> {
-        self.closed_shift_regex
-            .captures(string)
-            .map(|m| ClosedShift {
-                start_time: NaiveTime::from_hms_opt(
-                    get_u32(&m, "startHour"),
-                    get_u32(&m, "startMinute"),
-                    0,
-                )
-                .unwrap(),
-                stop_time: NaiveTime::from_hms_opt(
-                    get_u32(&m, "stopHour"),
-                    get_u32(&m, "stopMinute"),
-                    0,
-                )
-                .unwrap(),
+    fn parse_closed_shift(&self, string: &str) -> Option<Result<Line, String>> {
+        self.closed_shift_regex.captures(string).map(|m| {
+            Ok(ClosedShift {
+                start_time: get_time(&m, "startHour", "startMinute")?,
+                stop_time: get_time(&m, "stopHour", "stopMinute")?,
             })
+        })
+    }
+
+    fn parse_on_call(&self, string: &str) -> Option<Result<Line, String>> {
+        self.on_call_regex.captures(string).map(|m| {
+            Ok(OnCall {
+                start_time: get_time(&m, "startHour", "startMinute")?,
+                stop_time: match m.name("stopHour") {
+                    Some(_) => Some(get_time(&m, "stopHour", "stopMinute")?),
+                    None => None,
+                },
+            })
+        })
+    }
+
+    fn parse_utc_offset(&self, string: &str) -> Option<Line> {
+        self.utc_offset_regex
+            .captures(string)
+            .and_then(|m| m.name("offset").unwrap().as_str().parse().ok())
+            .map(|offset| UtcOffset { offset })
     }
 
     fn parse_duration_shift(&self, string: &str) -> Option<Line> {
@@ -363,24 +748,14 @@ impl Parser {
             })
     }
 
-    fn parse_special_shift(&self, string: &str) -> Option<Line> {
-        self.special_shift_regex
-            .captures(string)
-            .map(|m| SpecialShift {
+    fn parse_special_shift(&self, string: &str) -> Option<Result<Line, String>> {
+        self.special_shift_regex.captures(string).map(|m| {
+            Ok(SpecialShift {
                 text: String::from(m.name("text").unwrap().as_str()),
-                start_time: NaiveTime::from_hms_opt(
-                    get_u32(&m, "startHour"),
-                    get_u32(&m, "startMinute"),
-                    0,
-                )
-                .unwrap(),
-                stop_time: NaiveTime::from_hms_opt(
-                    get_u32(&m, "stopHour"),
-                    get_u32(&m, "stopMinute"),
-                    0,
-                )
-                .unwrap(),
+                start_time: get_time(&m, "startHour", "startMinute")?,
+                stop_time: get_time(&m, "stopHour", "stopMinute")?,
             })
+        })
     }
 
     fn parse_special_day(&self, string: &str) -> Option<Line> {
@@ -393,7 +768,14 @@ impl Parser {
         self.blank_regex.captures(string).map(|_| Blank)
     }
 
+    #[cfg(test)]
     pub fn parse_document(&self, week: IsoWeek, string: &str) -> Document {
+        self.try_parse_document(week, string)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Parses a document, or returns the first line that could not be parsed
+    pub fn try_parse_document(&self, week: IsoWeek, string: &str) -> Result<Document, ParseError> {
         // Far from pretty, but works..
 
         let mut preamble: Vec<Line> = Vec::new();
@@ -401,11 +783,15 @@ impl Parser {
         let mut current_date: Option<NaiveDate> = None;
         let mut current_day_lines: Vec<Line> = Vec::new();
 
-        let lines = string.lines().enumerate().map(|(line_num, l)| {
-            self.parse_line(l)
-                .unwrap_or_else(|| panic!("line {} could not be parsed: {}", line_num, l))
+        let lines = string.lines().enumerate().map(|(index, l)| {
+            self.parse_line(l).map_err(|reason| ParseError {
+                line_number: index + 1,
+                line: String::from(l),
+                reason,
+            })
         });
         for line in lines {
+            let line = line?;
             match current_date {
                 Some(date) => match line {
                     DayHeader { date: new_date } => {
@@ -431,11 +817,11 @@ impl Parser {
                 lines: current_day_lines,
             })
         }
-        Document {
+        Ok(Document {
             week,
             preamble,
             days,
-        }
+        })
     }
 }
 
@@ -1,36 +1,38 @@
 use crate::document::Line::{
-    Blank, ClosedShift, Comment, DayHeader, DurationShift, OpenShift, SpecialDay, SpecialShift,
+    Blank, ClosedShift, Comment, DayHeader, DurationShift, OnCall, OpenShift, SpecialDay,
+    SpecialShift, UtcOffset,
 };
-use crate::document::{Day, Document, Parser};
-use chrono::{Datelike, Duration, IsoWeek, NaiveDate, NaiveTime, TimeDelta};
+use crate::document::{Day, Document, ParseError, Parser};
+use crate::locale::Locale;
+use chrono::{Datelike, Duration, FixedOffset, IsoWeek, NaiveDate, NaiveTime, TimeDelta};
 
 #[test]
 fn read_line() {
-    let parser = Parser::new();
+    let parser = Parser::new(Locale::English);
 
     assert_eq!(
-        Option::Some(Comment {
+        Ok(Comment {
             text: String::from("hello")
         }),
         parser.parse_line("# hello")
     );
 
     assert_eq!(
-        Option::Some(DayHeader {
+        Ok(DayHeader {
             date: NaiveDate::from_ymd_opt(2021, 9, 13).unwrap()
         }),
         parser.parse_line("[monday 2021-09-13]")
     );
 
     assert_eq!(
-        Option::Some(OpenShift {
+        Ok(OpenShift {
             start_time: time_hm(8, 12)
         }),
         parser.parse_line("* 08:12-")
     );
 
     assert_eq!(
-        Option::Some(ClosedShift {
+        Ok(ClosedShift {
             start_time: time_hm(8, 24),
             stop_time: time_hm(9, 12)
         }),
@@ -38,14 +40,14 @@ fn read_line() {
     );
 
     assert_eq!(
-        Option::Some(SpecialDay {
+        Ok(SpecialDay {
             text: String::from("hello")
         }),
         parser.parse_line("* hello")
     );
 
     assert_eq!(
-        Option::Some(SpecialShift {
+        Ok(SpecialShift {
             text: String::from("VAB"),
             start_time: time_hm(13, 5),
             stop_time: time_hm(20, 2)
@@ -54,7 +56,30 @@ fn read_line() {
     );
 
     assert_eq!(
-        Option::Some(DurationShift {
+        Ok(OnCall {
+            start_time: time_hm(17, 0),
+            stop_time: Some(time_hm(8, 0))
+        }),
+        parser.parse_line("* oncall 17:00-08:00")
+    );
+
+    assert_eq!(
+        Ok(OnCall {
+            start_time: time_hm(17, 0),
+            stop_time: None
+        }),
+        parser.parse_line("* oncall 17:00-")
+    );
+
+    assert_eq!(
+        Ok(UtcOffset {
+            offset: FixedOffset::west_opt(4 * 3600 + 30 * 60).unwrap()
+        }),
+        parser.parse_line("* offset -04:30")
+    );
+
+    assert_eq!(
+        Ok(DurationShift {
             text: String::from("balance"),
             duration: TimeDelta::try_hours(20).unwrap()
         }),
@@ -62,19 +87,19 @@ fn read_line() {
     );
 
     assert_eq!(
-        Option::Some(DurationShift {
+        Ok(DurationShift {
             text: String::from("balance"),
             duration: TimeDelta::try_minutes(-5 * 60 - 6).unwrap()
         }),
         parser.parse_line("* balance -5h -6m")
     );
 
-    assert_eq!(Option::Some(Blank), parser.parse_line(""));
+    assert_eq!(Ok(Blank), parser.parse_line(""));
 }
 
 #[test]
 fn serde_duration() {
-    let parser = Parser::new();
+    let parser = Parser::new(Locale::English);
 
     let line = "* carry 1h 30m";
     let parsed = parser.parse_line(line).unwrap();
@@ -93,7 +118,7 @@ fn deserialize() {
     let serialized_document = example_1_text();
     let document = example_1_document();
 
-    let parser = Parser::new();
+    let parser = Parser::new(Locale::English);
     let parsed = parser.parse_document(week, &serialized_document);
     assert_eq!(document, parsed)
 }
@@ -106,6 +131,81 @@ fn serialize() {
     assert_eq!(serialized_document, document.to_string())
 }
 
+#[test]
+fn day_headers_in_the_configured_locale_are_parsed() {
+    let parser = Parser::new(Locale::Swedish);
+
+    assert_eq!(
+        Ok(DayHeader {
+            date: NaiveDate::from_ymd_opt(2024, 1, 27).unwrap()
+        }),
+        parser.parse_line("[lördag 2024-01-27]")
+    );
+    assert_eq!(
+        Ok(DayHeader {
+            date: NaiveDate::from_ymd_opt(2024, 1, 28).unwrap()
+        }),
+        parser.parse_line("[Söndag 2024-01-28]")
+    );
+}
+
+#[test]
+fn day_headers_must_name_a_weekday() {
+    let parser = Parser::new(Locale::Swedish);
+
+    assert_eq!(
+        Ok(DayHeader {
+            date: NaiveDate::from_ymd_opt(2024, 1, 27).unwrap()
+        }),
+        parser.parse_line("[söndag 2024-01-27]")
+    );
+    assert_eq!(
+        Err(String::from("lordag is not the name of a weekday")),
+        parser.parse_line("[lordag 2024-01-27]")
+    );
+    assert_eq!(
+        Err(String::from("lördag is not the name of a weekday")),
+        Parser::new(Locale::English).parse_line("[lördag 2024-01-27]")
+    );
+}
+
+#[test]
+fn errors_tell_which_line_could_not_be_parsed() {
+    let parser = Parser::new(Locale::English);
+
+    assert_eq!(
+        Err(ParseError {
+            line_number: 3,
+            line: String::from("* 8:00-"),
+            reason: String::from("not a known kind of line"),
+        }),
+        parser.try_parse_document(
+            example_1_week(),
+            "[monday 2021-09-13]\n* 08:00-12:00\n* 8:00-\n"
+        )
+    );
+}
+
+#[test]
+fn serialize_in_swedish() {
+    let document = Document {
+        week: example_1_week(),
+        preamble: vec![],
+        days: vec![Day {
+            date: NaiveDate::from_ymd_opt(2024, 1, 27).unwrap(),
+            lines: vec![ClosedShift {
+                start_time: time_hm(8, 0),
+                stop_time: time_hm(12, 0),
+            }],
+        }],
+    };
+
+    assert_eq!(
+        "[lördag 2024-01-27]\n* 08:00-12:00\n",
+        document.to_string_in(Locale::Swedish)
+    );
+}
+
 #[test]
 fn replacing_day_that_does_not_exist() {
     let document = Document {
@@ -245,3 +345,191 @@ fn example_1_text() -> String {
 ",
     )
 }
+
+#[test]
+fn shifts_are_inserted_in_order() {
+    let day = Day::create(
+        NaiveDate::from_ymd_opt(2024, 1, 22).unwrap(),
+        vec![
+            ClosedShift {
+                start_time: time_hm(8, 0),
+                stop_time: time_hm(11, 0),
+            },
+            ClosedShift {
+                start_time: time_hm(13, 0),
+                stop_time: time_hm(17, 0),
+            },
+            Comment {
+                text: String::from("Long lunch"),
+            },
+            Blank,
+        ],
+    );
+
+    let day = day
+        .inserting_shift(ClosedShift {
+            start_time: time_hm(11, 30),
+            stop_time: time_hm(12, 30),
+        })
+        .inserting_shift(ClosedShift {
+            start_time: time_hm(18, 0),
+            stop_time: time_hm(19, 0),
+        });
+
+    assert_eq!(
+        vec![
+            ClosedShift {
+                start_time: time_hm(8, 0),
+                stop_time: time_hm(11, 0),
+            },
+            ClosedShift {
+                start_time: time_hm(11, 30),
+                stop_time: time_hm(12, 30),
+            },
+            ClosedShift {
+                start_time: time_hm(13, 0),
+                stop_time: time_hm(17, 0),
+            },
+            ClosedShift {
+                start_time: time_hm(18, 0),
+                stop_time: time_hm(19, 0),
+            },
+            Comment {
+                text: String::from("Long lunch"),
+            },
+            Blank,
+        ],
+        day.lines
+    );
+}
+
+#[test]
+fn on_call_is_added_after_shifts_and_closed() {
+    let day = Day::create(
+        NaiveDate::from_ymd_opt(2024, 1, 22).unwrap(),
+        vec![
+            ClosedShift {
+                start_time: time_hm(8, 0),
+                stop_time: time_hm(16, 0),
+            },
+            Comment {
+                text: String::from("Release day"),
+            },
+        ],
+    );
+
+    let day = day.adding_on_call(OnCall {
+        start_time: time_hm(17, 0),
+        stop_time: None,
+    });
+    assert_eq!(Some(time_hm(17, 0)), day.open_on_call());
+
+    let day = day.closing_on_call(time_hm(8, 0));
+    assert_eq!(None, day.open_on_call());
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-16:00\n* oncall 17:00-08:00\n# Release day\n",
+        day.to_string()
+    );
+}
+
+#[test]
+fn utc_offset_is_added_once_before_blank_lines() {
+    let day = Day::create(
+        NaiveDate::from_ymd_opt(2024, 1, 22).unwrap(),
+        vec![
+            OpenShift {
+                start_time: time_hm(8, 0),
+            },
+            Blank,
+        ],
+    );
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+
+    let day = day.with_utc_offset(tokyo).with_utc_offset(tokyo);
+
+    assert_eq!(Some(tokyo), day.utc_offset());
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-\n* offset +09:00\n\n",
+        day.to_string()
+    );
+}
+
+#[test]
+fn removing_shift() {
+    let day = Day::create(
+        NaiveDate::from_ymd_opt(2024, 1, 22).unwrap(),
+        vec![
+            ClosedShift {
+                start_time: time_hm(8, 0),
+                stop_time: time_hm(11, 0),
+            },
+            OpenShift {
+                start_time: time_hm(13, 0),
+            },
+        ],
+    );
+
+    assert_eq!(None, day.removing_shift(time_hm(9, 0)));
+    assert_eq!(
+        vec![OpenShift {
+            start_time: time_hm(13, 0),
+        }],
+        day.removing_shift(time_hm(8, 0)).unwrap().lines
+    );
+}
+
+#[test]
+fn removing_the_last_day_removes_the_blank_before_it() {
+    let parser = Parser::new(Locale::English);
+    let document = parser.parse_document(
+        example_1_week(),
+        "[monday 2020-07-13]\n* 08:00-12:00\n\n[tuesday 2020-07-14]\n* 08:00-\n",
+    );
+
+    assert_eq!(
+        "[monday 2020-07-13]\n* 08:00-12:00\n",
+        document
+            .removing_day(NaiveDate::from_ymd_opt(2020, 7, 14).unwrap())
+            .to_string()
+    );
+    assert_eq!(
+        "[tuesday 2020-07-14]\n* 08:00-\n",
+        document
+            .removing_day(NaiveDate::from_ymd_opt(2020, 7, 13).unwrap())
+            .to_string()
+    );
+}
+
+#[test]
+fn day_headers_with_the_wrong_weekday_are_found() {
+    let parser = Parser::new(Locale::Swedish);
+
+    assert_eq!(
+        vec![(4, String::from("2024-01-27 is a lördag"))],
+        parser.misnamed_day_headers(
+            "[fredag 2024-01-26]\n* 08:00-12:00\n\n[söndag 2024-01-27]\n* 10:00-12:00\n"
+        )
+    );
+}
+
+#[test]
+fn times_must_be_within_a_day() {
+    let parser = Parser::new(Locale::English);
+
+    assert_eq!(
+        Err(String::from("not a valid time")),
+        parser.parse_line("* 25:00-26:00")
+    );
+    assert_eq!(
+        Err(String::from("not a valid time")),
+        parser.parse_line("* 08:60-")
+    );
+    assert_eq!(
+        Err(String::from("not a valid time")),
+        parser.parse_line("* oncall 17:00-24:00")
+    );
+    assert_eq!(
+        Err(String::from("not a valid time")),
+        parser.parse_line("* VAB 13:00-17:75")
+    );
+}
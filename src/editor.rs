@@ -0,0 +1,45 @@
+use std::path::Path;
+
+/// The editor used when neither `VISUAL` nor `EDITOR` is set
+const DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+/// The editor command from `VISUAL`, then `EDITOR`, then a default, split into the program
+/// and its arguments, like `code --wait`
+pub(crate) fn editor_command(visual: Option<String>, editor: Option<String>) -> Vec<String> {
+    [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(|command| {
+            command
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<String>>()
+        })
+        .find(|command| !command.is_empty())
+        .unwrap_or_else(|| vec![String::from(DEFAULT_EDITOR)])
+}
+
+/// The arguments for opening a file in an editor, at a certain line if the editor is known
+/// to support that
+pub(crate) fn editor_args(program: &str, path: &Path, line_number: Option<usize>) -> Vec<String> {
+    let path = path.display().to_string();
+    let Some(line_number) = line_number else {
+        return vec![path];
+    };
+    let name = Path::new(program)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match name.as_str() {
+        "vi" | "vim" | "nvim" | "gvim" | "view" | "nano" | "pico" | "emacs" | "emacsclient"
+        | "micro" | "kak" | "joe" | "mg" | "ne" => vec![format!("+{}", line_number), path],
+        "code" | "code-insiders" | "codium" => {
+            vec![String::from("--goto"), format!("{}:{}", path, line_number)]
+        }
+        "subl" | "hx" | "helix" | "zed" => vec![format!("{}:{}", path, line_number)],
+        _ => vec![path],
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use crate::editor::{editor_args, editor_command};
+
+#[test]
+fn visual_is_preferred_over_editor() {
+    assert_eq!(
+        editor_command(Some(String::from("code --wait")), Some(String::from("vim"))),
+        vec!["code", "--wait"]
+    );
+    assert_eq!(
+        editor_command(Some(String::from(" ")), Some(String::from("vim"))),
+        vec!["vim"]
+    );
+    assert_eq!(
+        editor_command(None, Some(String::from("nano"))),
+        vec!["nano"]
+    );
+}
+
+#[test]
+fn there_is_a_default_editor() {
+    assert_eq!(editor_command(None, None).len(), 1);
+}
+
+#[test]
+fn editors_are_told_which_line_to_jump_to() {
+    let path = Path::new("/tmp/2024-W04.txt");
+
+    assert_eq!(
+        editor_args("/usr/bin/nvim", path, Some(7)),
+        vec!["+7", "/tmp/2024-W04.txt"]
+    );
+    assert_eq!(
+        editor_args("code", path, Some(7)),
+        vec!["--goto", "/tmp/2024-W04.txt:7"]
+    );
+    assert_eq!(
+        editor_args("hx", path, Some(7)),
+        vec!["/tmp/2024-W04.txt:7"]
+    );
+    assert_eq!(editor_args("ed", path, Some(7)), vec!["/tmp/2024-W04.txt"]);
+    assert_eq!(editor_args("vim", path, None), vec!["/tmp/2024-W04.txt"]);
+}
@@ -0,0 +1,350 @@
+use crate::billing::billed_entries;
+use crate::config::{BillingConfig, WorkWeekConfig};
+use crate::document::Day;
+use crate::document::Line::{ClosedShift, SpecialDay, SpecialShift};
+use crate::report::{duration_for_line, format_hours_minutes};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+const ICS_UID_DOMAIN: &str = "tracker.skagedal.tech";
+
+/// Render days as an iCalendar document. Closed and special shifts become timed events,
+/// special days become all-day events. Open shifts are left out.
+pub fn to_ics(days: &[Day], workweek: &WorkWeekConfig, stamp: NaiveDateTime) -> String {
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//skagedal//tracker//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    for day in days {
+        for line in &day.lines {
+            match line {
+                ClosedShift {
+                    start_time,
+                    stop_time,
+                } => lines.extend(timed_event(
+                    day,
+                    workweek,
+                    (*start_time, *stop_time),
+                    "shift",
+                    "Work",
+                    stamp,
+                )),
+                SpecialShift {
+                    text,
+                    start_time,
+                    stop_time,
+                } => lines.extend(timed_event(
+                    day,
+                    workweek,
+                    (*start_time, *stop_time),
+                    "special",
+                    text,
+                    stamp,
+                )),
+                SpecialDay { text } => lines.extend(all_day_event(day.date, text, stamp)),
+                _ => {}
+            }
+        }
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut string = String::new();
+    for line in lines {
+        string.push_str(&fold_ics_line(&line));
+        string.push_str("\r\n");
+    }
+    string
+}
+
+/// An event for a shift. The kind of entry is part of the UID, so that a shift and a
+/// special shift starting at the same time are different events.
+fn timed_event(
+    day: &Day,
+    workweek: &WorkWeekConfig,
+    (start_time, stop_time): (NaiveTime, NaiveTime),
+    kind: &str,
+    summary: &str,
+    stamp: NaiveDateTime,
+) -> Vec<String> {
+    let start = day.date.and_time(start_time);
+    // A stop time before the start time means the shift ended after midnight
+    let stop = if stop_time < start_time {
+        (day.date + TimeDelta::try_days(1).unwrap()).and_time(stop_time)
+    } else {
+        day.date.and_time(stop_time)
+    };
+    vec![
+        String::from("BEGIN:VEVENT"),
+        format!(
+            "UID:{}-{}@{}",
+            start.format("%Y%m%dT%H%M"),
+            kind,
+            ICS_UID_DOMAIN
+        ),
+        format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART{}", ics_date_time(day, workweek, start)),
+        format!("DTEND{}", ics_date_time(day, workweek, stop)),
+        format!("SUMMARY:{}", escape_ics_text(summary)),
+        String::from("END:VEVENT"),
+    ]
+}
+
+/// A wall-clock time of a day as an iCalendar value, with the parameters it needs. Times on
+/// days with a UTC offset of their own are written in UTC, other times are in the home time
+/// zone if there is one.
+fn ics_date_time(day: &Day, workweek: &WorkWeekConfig, time: NaiveDateTime) -> String {
+    match (day.utc_offset(), workweek.time_zone) {
+        (Some(offset), _) => {
+            let utc = time - Duration::seconds(offset.local_minus_utc().into());
+            format!(":{}", utc.format("%Y%m%dT%H%M%SZ"))
+        }
+        (None, Some(zone)) => format!(";TZID={}:{}", zone.name(), time.format("%Y%m%dT%H%M%S")),
+        (None, None) => format!(":{}", time.format("%Y%m%dT%H%M%S")),
+    }
+}
+
+fn all_day_event(date: NaiveDate, summary: &str, stamp: NaiveDateTime) -> Vec<String> {
+    vec![
+        String::from("BEGIN:VEVENT"),
+        format!(
+            "UID:{}-{}@{}",
+            date.format("%Y%m%d"),
+            summary.to_lowercase(),
+            ICS_UID_DOMAIN
+        ),
+        format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + TimeDelta::try_days(1).unwrap()).format("%Y%m%d")
+        ),
+        format!("SUMMARY:{}", escape_ics_text(summary)),
+        String::from("TRANSP:TRANSPARENT"),
+        String::from("END:VEVENT"),
+    ]
+}
+
+/// Splits a content line longer than 75 octets into lines that continue with a space, as
+/// RFC 5545 asks for. Lines are never split inside a character.
+fn fold_ics_line(line: &str) -> String {
+    const MAX_OCTETS: usize = 75;
+    let mut folded = String::new();
+    let mut octets = 0;
+    for char in line.chars() {
+        if octets + char.len_utf8() > MAX_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(char);
+        octets += char.len_utf8();
+    }
+    folded
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// A column in the CSV export
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum CsvColumn {
+    Date,
+    Start,
+    Stop,
+    Duration,
+    /// The exact duration, when durations are rounded for billing
+    RawDuration,
+    Kind,
+    Note,
+}
+
+impl CsvColumn {
+    /// The columns included unless others are asked for
+    pub fn defaults() -> Vec<CsvColumn> {
+        vec![
+            CsvColumn::Date,
+            CsvColumn::Start,
+            CsvColumn::Stop,
+            CsvColumn::Duration,
+            CsvColumn::Kind,
+            CsvColumn::Note,
+        ]
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Date => "date",
+            CsvColumn::Start => "start",
+            CsvColumn::Stop => "stop",
+            CsvColumn::Duration => "duration",
+            CsvColumn::RawDuration => "raw_duration",
+            CsvColumn::Kind => "kind",
+            CsvColumn::Note => "note",
+        }
+    }
+}
+
+/// How durations are written in the CSV export
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum DurationFormat {
+    /// Decimal hours, e.g. 7.50
+    Decimal,
+    /// Hours and minutes, e.g. 7:30
+    HoursMinutes,
+}
+
+pub struct CsvOptions {
+    pub columns: Vec<CsvColumn>,
+    pub separator: char,
+    pub duration_format: DurationFormat,
+    pub totals: bool,
+    /// One row per billed entry, with durations rounded according to the billing settings
+    pub billing: bool,
+}
+
+struct CsvRow {
+    date: NaiveDate,
+    start: Option<NaiveTime>,
+    stop: Option<NaiveTime>,
+    duration: Duration,
+    raw_duration: Duration,
+    kind: String,
+    note: String,
+}
+
+/// Render days as one CSV row per closed shift, special shift and special day, or when
+/// billing, one row per billed entry.
+pub fn to_csv(
+    days: &[Day],
+    workweek: &WorkWeekConfig,
+    billing: &BillingConfig,
+    options: &CsvOptions,
+) -> String {
+    let rows: Vec<CsvRow> = if options.billing {
+        billed_entries(days, workweek, billing)
+            .into_iter()
+            .map(|entry| CsvRow {
+                date: entry.date,
+                start: entry.start,
+                stop: entry.stop,
+                duration: entry.rounded,
+                raw_duration: entry.raw,
+                kind: entry.kind,
+                note: entry.note,
+            })
+            .collect()
+    } else {
+        days.iter()
+            .flat_map(|day| csv_rows(day, workweek))
+            .collect()
+    };
+
+    let mut string = String::new();
+    let headers: Vec<String> = options
+        .columns
+        .iter()
+        .map(|column| String::from(column.header()))
+        .collect();
+    push_csv_record(&mut string, &headers, options.separator);
+    for row in &rows {
+        let fields: Vec<String> = options
+            .columns
+            .iter()
+            .map(|column| match column {
+                CsvColumn::Date => row.date.to_string(),
+                CsvColumn::Start => format_optional_time(row.start),
+                CsvColumn::Stop => format_optional_time(row.stop),
+                CsvColumn::Duration => format_csv_duration(&row.duration, options.duration_format),
+                CsvColumn::RawDuration => {
+                    format_csv_duration(&row.raw_duration, options.duration_format)
+                }
+                CsvColumn::Kind => row.kind.clone(),
+                CsvColumn::Note => row.note.clone(),
+            })
+            .collect();
+        push_csv_record(&mut string, &fields, options.separator);
+    }
+    if options.totals {
+        let total: Duration = rows.iter().map(|row| row.duration).sum();
+        let raw_total: Duration = rows.iter().map(|row| row.raw_duration).sum();
+        let fields: Vec<String> = options
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match column {
+                CsvColumn::Duration => format_csv_duration(&total, options.duration_format),
+                CsvColumn::RawDuration => format_csv_duration(&raw_total, options.duration_format),
+                _ if index == 0 => String::from("total"),
+                _ => String::new(),
+            })
+            .collect();
+        push_csv_record(&mut string, &fields, options.separator);
+    }
+    string
+}
+
+fn csv_rows(day: &Day, workweek: &WorkWeekConfig) -> Vec<CsvRow> {
+    let mut rows: Vec<CsvRow> = Vec::new();
+    for (index, line) in day.lines.iter().enumerate() {
+        let (start, stop, kind) = match line {
+            ClosedShift {
+                start_time,
+                stop_time,
+            } => (Some(*start_time), Some(*stop_time), String::from("work")),
+            SpecialShift {
+                text,
+                start_time,
+                stop_time,
+            } => (Some(*start_time), Some(*stop_time), text.to_lowercase()),
+            SpecialDay { text } => (None, None, text.to_lowercase()),
+            _ => continue,
+        };
+        let duration = duration_for_line(line, day, None, workweek);
+        rows.push(CsvRow {
+            date: day.date,
+            start,
+            stop,
+            duration,
+            raw_duration: duration,
+            kind,
+            note: day.note_for_line(index),
+        });
+    }
+    rows
+}
+
+fn format_optional_time(time: Option<NaiveTime>) -> String {
+    time.map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn format_csv_duration(duration: &Duration, format: DurationFormat) -> String {
+    match format {
+        DurationFormat::Decimal => format!("{:.2}", duration.num_minutes() as f64 / 60.0),
+        DurationFormat::HoursMinutes => format_hours_minutes(duration),
+    }
+}
+
+fn push_csv_record(string: &mut String, fields: &[String], separator: char) {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|field| escape_csv_field(field, separator))
+        .collect();
+    string.push_str(&escaped.join(&separator.to_string()));
+    string.push('\n');
+}
+
+fn escape_csv_field(field: &str, separator: char) -> String {
+    if field.contains(separator) || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,318 @@
+use crate::config::{BillingConfig, Rounding, RoundingScope, WorkWeekConfig};
+use crate::document::{Day, Line};
+use crate::export::{CsvColumn, CsvOptions, DurationFormat, to_csv, to_ics};
+use crate::testutils::{naive_date, naive_date_time, naive_time};
+use chrono::FixedOffset;
+
+#[test]
+fn shifts_become_timed_events() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![
+            Line::ClosedShift {
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(11, 54),
+            },
+            Line::SpecialShift {
+                text: String::from("VAB"),
+                start_time: naive_time(13, 0),
+                stop_time: naive_time(17, 0),
+            },
+            Line::OpenShift {
+                start_time: naive_time(18, 0),
+            },
+        ],
+    }];
+
+    assert_eq!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//skagedal//tracker//EN\r\n\
+         CALSCALE:GREGORIAN\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:20240122T0900-shift@tracker.skagedal.tech\r\n\
+         DTSTAMP:20240126T120000Z\r\n\
+         DTSTART:20240122T090000\r\n\
+         DTEND:20240122T115400\r\n\
+         SUMMARY:Work\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:20240122T1300-special@tracker.skagedal.tech\r\n\
+         DTSTAMP:20240126T120000Z\r\n\
+         DTSTART:20240122T130000\r\n\
+         DTEND:20240122T170000\r\n\
+         SUMMARY:VAB\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        to_ics(
+            &days,
+            &WorkWeekConfig::default(),
+            naive_date_time(2024, 1, 26, 12, 0),
+        )
+    );
+}
+
+#[test]
+fn special_days_become_all_day_events() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 26),
+        lines: vec![Line::SpecialDay {
+            text: String::from("Vacation"),
+        }],
+    }];
+
+    let ics = to_ics(
+        &days,
+        &WorkWeekConfig::default(),
+        naive_date_time(2024, 1, 26, 12, 0),
+    );
+
+    assert!(ics.contains("UID:20240126-vacation@tracker.skagedal.tech\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20240126\r\n"));
+    assert!(ics.contains("DTEND;VALUE=DATE:20240127\r\n"));
+    assert!(ics.contains("SUMMARY:Vacation\r\n"));
+}
+
+#[test]
+fn shifts_past_midnight_end_the_next_day() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 26),
+        lines: vec![Line::ClosedShift {
+            start_time: naive_time(22, 0),
+            stop_time: naive_time(1, 30),
+        }],
+    }];
+
+    let ics = to_ics(
+        &days,
+        &WorkWeekConfig::default(),
+        naive_date_time(2024, 1, 26, 12, 0),
+    );
+
+    assert!(ics.contains("DTEND:20240127T013000\r\n"));
+}
+
+#[test]
+fn a_shift_and_a_special_shift_at_the_same_time_are_different_events() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![
+            Line::ClosedShift {
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(12, 0),
+            },
+            Line::SpecialShift {
+                text: String::from("VAB"),
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(12, 0),
+            },
+        ],
+    }];
+
+    let ics = to_ics(
+        &days,
+        &WorkWeekConfig::default(),
+        naive_date_time(2024, 1, 26, 12, 0),
+    );
+
+    assert!(ics.contains("UID:20240122T0900-shift@tracker.skagedal.tech\r\n"));
+    assert!(ics.contains("UID:20240122T0900-special@tracker.skagedal.tech\r\n"));
+}
+
+#[test]
+fn times_are_in_the_home_time_zone_or_in_utc_when_away() {
+    let shift = Line::ClosedShift {
+        start_time: naive_time(9, 0),
+        stop_time: naive_time(17, 0),
+    };
+    let days = vec![
+        Day {
+            date: naive_date(2024, 1, 22),
+            lines: vec![shift.clone()],
+        },
+        Day {
+            date: naive_date(2024, 1, 23),
+            lines: vec![
+                shift,
+                Line::UtcOffset {
+                    offset: FixedOffset::east_opt(9 * 3600).unwrap(),
+                },
+            ],
+        },
+    ];
+    let workweek = WorkWeekConfig {
+        time_zone: Some(chrono_tz::Europe::Stockholm),
+        ..Default::default()
+    };
+
+    let ics = to_ics(&days, &workweek, naive_date_time(2024, 1, 26, 12, 0));
+
+    assert!(ics.contains("DTSTART;TZID=Europe/Stockholm:20240122T090000\r\n"));
+    assert!(ics.contains("DTEND;TZID=Europe/Stockholm:20240122T170000\r\n"));
+    assert!(ics.contains("DTSTART:20240123T000000Z\r\n"));
+    assert!(ics.contains("DTEND:20240123T080000Z\r\n"));
+}
+
+#[test]
+fn long_lines_are_folded() {
+    let text = "Semester på västkusten med hela familjen och några vänner".repeat(2);
+    let days = vec![Day {
+        date: naive_date(2024, 1, 26),
+        lines: vec![Line::SpecialDay { text: text.clone() }],
+    }];
+
+    let ics = to_ics(
+        &days,
+        &WorkWeekConfig::default(),
+        naive_date_time(2024, 1, 26, 12, 0),
+    );
+
+    assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+    assert!(
+        ics.replace("\r\n ", "")
+            .contains(&format!("\r\nSUMMARY:{}\r\n", text))
+    );
+}
+
+#[test]
+fn timesheet_has_one_row_per_entry() {
+    let days = vec![
+        Day {
+            date: naive_date(2024, 1, 22),
+            lines: vec![
+                Line::ClosedShift {
+                    start_time: naive_time(9, 0),
+                    stop_time: naive_time(11, 45),
+                },
+                Line::Comment {
+                    text: String::from("Planning, mostly"),
+                },
+                Line::OpenShift {
+                    start_time: naive_time(12, 30),
+                },
+            ],
+        },
+        Day {
+            date: naive_date(2024, 1, 23),
+            lines: vec![Line::SpecialDay {
+                text: String::from("Sick"),
+            }],
+        },
+    ];
+
+    assert_eq!(
+        "date,start,stop,duration,kind,note\n\
+         2024-01-22,09:00,11:45,2.75,work,\"Planning, mostly\"\n\
+         2024-01-23,,,8.00,sick,\n",
+        to_csv(
+            &days,
+            &WorkWeekConfig::default(),
+            &BillingConfig::default(),
+            &csv_options()
+        )
+    );
+}
+
+#[test]
+fn timesheet_shifts_past_midnight_end_the_next_day() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 26),
+        lines: vec![Line::ClosedShift {
+            start_time: naive_time(22, 0),
+            stop_time: naive_time(1, 30),
+        }],
+    }];
+
+    assert_eq!(
+        "date,start,stop,duration,kind,note\n\
+         2024-01-26,22:00,01:30,3.50,work,\n",
+        to_csv(
+            &days,
+            &WorkWeekConfig::default(),
+            &BillingConfig::default(),
+            &csv_options()
+        )
+    );
+}
+
+#[test]
+fn timesheet_with_selected_columns_and_totals() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![
+            Line::ClosedShift {
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(11, 45),
+            },
+            Line::SpecialShift {
+                text: String::from("VAB"),
+                start_time: naive_time(13, 0),
+                stop_time: naive_time(17, 5),
+            },
+        ],
+    }];
+    let options = CsvOptions {
+        columns: vec![CsvColumn::Date, CsvColumn::Kind, CsvColumn::Duration],
+        separator: '\t',
+        duration_format: DurationFormat::HoursMinutes,
+        totals: true,
+        billing: false,
+    };
+
+    assert_eq!(
+        "date\tkind\tduration\n\
+         2024-01-22\twork\t2:45\n\
+         2024-01-22\tvab\t4:05\n\
+         total\t\t6:50\n",
+        to_csv(
+            &days,
+            &WorkWeekConfig::default(),
+            &BillingConfig::default(),
+            &options
+        )
+    );
+}
+
+#[test]
+fn billing_timesheet_has_rounded_durations() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![
+            Line::ClosedShift {
+                start_time: naive_time(9, 0),
+                stop_time: naive_time(11, 40),
+            },
+            Line::SpecialDay {
+                text: String::from("Sick"),
+            },
+        ],
+    }];
+    let billing = BillingConfig {
+        rounding: Rounding::Up,
+        granularity: chrono::Duration::minutes(15),
+        per: RoundingScope::Shift,
+    };
+    let options = CsvOptions {
+        columns: vec![CsvColumn::Date, CsvColumn::Duration, CsvColumn::RawDuration],
+        billing: true,
+        totals: true,
+        ..csv_options()
+    };
+
+    assert_eq!(
+        "date,duration,raw_duration\n\
+         2024-01-22,2.75,2.67\n\
+         total,2.75,2.67\n",
+        to_csv(&days, &WorkWeekConfig::default(), &billing, &options)
+    );
+}
+
+fn csv_options() -> CsvOptions {
+    CsvOptions {
+        columns: CsvColumn::defaults(),
+        separator: ',',
+        duration_format: DurationFormat::Decimal,
+        totals: false,
+        billing: false,
+    }
+}
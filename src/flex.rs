@@ -0,0 +1,50 @@
+use crate::config::{FlexConfig, FlexPeriod};
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// A balance carried over to a new week
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CarryOver {
+    pub balance: Duration,
+    /// What was lost on the way; negative if debt was written off
+    pub forfeited: Duration,
+}
+
+/// Carries a balance over from the week starting at `from` to the week starting at `to`,
+/// keeping it within the ceiling and floor, and forfeiting what is above the maximum
+/// carry-over if a new period starts.
+pub(crate) fn carry_over(
+    balance: Duration,
+    from: NaiveDate,
+    to: NaiveDate,
+    flex: &FlexConfig,
+) -> CarryOver {
+    let mut kept = balance;
+    if let Some(ceiling) = flex.ceiling {
+        kept = kept.min(ceiling);
+    }
+    if let Some(floor) = flex.floor {
+        kept = kept.max(floor);
+    }
+    if let Some(period) = flex.period
+        && period_of(from, period) != period_of(to, period)
+        && kept > flex.max_carry_over
+    {
+        kept = flex.max_carry_over;
+    }
+    CarryOver {
+        balance: kept,
+        forfeited: balance - kept,
+    }
+}
+
+/// The year and the number of the period in the year
+fn period_of(date: NaiveDate, period: FlexPeriod) -> (i32, u32) {
+    match period {
+        FlexPeriod::Month => (date.year(), date.month0()),
+        FlexPeriod::Quarter => (date.year(), date.month0() / 3),
+        FlexPeriod::Year => (date.year(), 0),
+    }
+}
+
+#[cfg(test)]
+mod tests;
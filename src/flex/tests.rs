@@ -0,0 +1,103 @@
+use chrono::Duration;
+
+use crate::config::{FlexConfig, FlexPeriod};
+use crate::flex::{CarryOver, carry_over};
+use crate::testutils::naive_date;
+
+fn flex() -> FlexConfig {
+    FlexConfig {
+        ceiling: Some(Duration::hours(40)),
+        floor: Some(Duration::hours(-40)),
+        period: Some(FlexPeriod::Quarter),
+        max_carry_over: Duration::hours(10),
+    }
+}
+
+#[test]
+fn balance_within_limits_is_kept() {
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(25),
+            forfeited: Duration::zero(),
+        },
+        carry_over(
+            Duration::hours(25),
+            naive_date(2024, 1, 22),
+            naive_date(2024, 1, 29),
+            &flex()
+        )
+    );
+}
+
+#[test]
+fn balance_is_kept_within_ceiling_and_floor() {
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(40),
+            forfeited: Duration::minutes(150),
+        },
+        carry_over(
+            Duration::minutes(42 * 60 + 30),
+            naive_date(2024, 1, 22),
+            naive_date(2024, 1, 29),
+            &flex()
+        )
+    );
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(-40),
+            forfeited: Duration::hours(-5),
+        },
+        carry_over(
+            Duration::hours(-45),
+            naive_date(2024, 1, 22),
+            naive_date(2024, 1, 29),
+            &flex()
+        )
+    );
+}
+
+#[test]
+fn balance_above_maximum_is_forfeited_in_new_period() {
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(10),
+            forfeited: Duration::hours(15),
+        },
+        carry_over(
+            Duration::hours(25),
+            naive_date(2024, 3, 25),
+            naive_date(2024, 4, 1),
+            &flex()
+        )
+    );
+    // Debt is not affected
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(-5),
+            forfeited: Duration::zero(),
+        },
+        carry_over(
+            Duration::hours(-5),
+            naive_date(2024, 3, 25),
+            naive_date(2024, 4, 1),
+            &flex()
+        )
+    );
+}
+
+#[test]
+fn no_limits_by_default() {
+    assert_eq!(
+        CarryOver {
+            balance: Duration::hours(100),
+            forfeited: Duration::zero(),
+        },
+        carry_over(
+            Duration::hours(100),
+            naive_date(2024, 12, 23),
+            naive_date(2024, 12, 30),
+            &FlexConfig::default()
+        )
+    );
+}
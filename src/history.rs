@@ -0,0 +1,122 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A local git repository keeping the history of the week files
+pub struct History {
+    dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    RunGit(io::Error),
+    GitFailed(String),
+    UnknownRevision(String),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::RunGit(err) => write!(f, "could not run git: {}", err),
+            HistoryError::GitFailed(message) => write!(f, "git failed: {}", message),
+            HistoryError::UnknownRevision(revision) => {
+                write!(f, "{} is not a known revision", revision)
+            }
+        }
+    }
+}
+
+impl History {
+    pub fn new(dir: &Path) -> Self {
+        History {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Commits all changes in the directory, creating the repository if needed.
+    /// Does nothing if there are no changes.
+    pub fn commit(&self, message: &str) -> Result<(), HistoryError> {
+        if !self.dir.join(".git").exists() {
+            self.init()?;
+        }
+        self.git(&["add", "--all"])?;
+        let status = self.git(&["status", "--porcelain"])?;
+        if status.stdout.is_empty() {
+            return Ok(());
+        }
+        self.git(&["commit", "--quiet", "--message", message])?;
+        Ok(())
+    }
+
+    /// One line per commit, newest first, optionally only those touching a certain file
+    pub fn log(&self, file_name: Option<&str>) -> Result<String, HistoryError> {
+        if !self.dir.join(".git").exists() {
+            return Ok(String::new());
+        }
+        let mut args = vec![
+            "log",
+            "--date=format:%Y-%m-%d %H:%M",
+            "--format=%h  %ad  %s",
+        ];
+        if let Some(file_name) = file_name {
+            args.push("--");
+            args.push(file_name);
+        }
+        let output = self.git(&args)?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Brings back a file as it was in a certain revision, and commits that
+    pub fn restore(&self, revision: &str, file_name: &str) -> Result<(), HistoryError> {
+        let commit = self.resolve(revision)?;
+        self.git(&["checkout", &commit, "--", file_name])?;
+        self.commit(&format!("Restore {} from {}", file_name, revision))
+    }
+
+    /// The full hash of the commit a revision refers to. Revisions that look like options are
+    /// not passed on to git.
+    fn resolve(&self, revision: &str) -> Result<String, HistoryError> {
+        let unknown = || HistoryError::UnknownRevision(String::from(revision));
+        if revision.starts_with('-') {
+            return Err(unknown());
+        }
+        let output = self
+            .git(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", revision),
+            ])
+            .map_err(|_| unknown())?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn init(&self) -> Result<(), HistoryError> {
+        self.git(&["init", "--quiet"])?;
+        // Commits should not fail just because no identity is configured globally
+        if self.git(&["config", "user.email"]).is_err() {
+            self.git(&["config", "user.name", "tracker"])?;
+            self.git(&["config", "user.email", "tracker@localhost"])?;
+        }
+        Ok(())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<Output, HistoryError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(HistoryError::RunGit)?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            Err(HistoryError::GitFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,69 @@
+use std::fs;
+
+use temp_dir::TempDir;
+
+use crate::history::History;
+
+#[test]
+fn commits_changes_and_lists_them() {
+    let tempdir = TempDir::new().unwrap();
+    let history = History::new(tempdir.path());
+
+    fs::write(tempdir.path().join("2024-W04.txt"), "[monday 2024-01-22]\n").unwrap();
+    history.commit("First").unwrap();
+    fs::write(tempdir.path().join("2024-W05.txt"), "[monday 2024-01-29]\n").unwrap();
+    history.commit("Second").unwrap();
+    // Nothing changed, so no commit
+    history.commit("Third").unwrap();
+
+    let log = history.log(None).unwrap();
+    let subjects: Vec<&str> = log
+        .lines()
+        .map(|line| line.rsplit("  ").next().unwrap())
+        .collect();
+    assert_eq!(vec!["Second", "First"], subjects);
+
+    let log = history.log(Some("2024-W04.txt")).unwrap();
+    assert_eq!(1, log.lines().count());
+}
+
+#[test]
+fn restores_an_earlier_version() {
+    let tempdir = TempDir::new().unwrap();
+    let history = History::new(tempdir.path());
+    let path = tempdir.path().join("2024-W04.txt");
+
+    fs::write(&path, "[monday 2024-01-22]\n* 08:00-12:00\n").unwrap();
+    history.commit("First").unwrap();
+    fs::write(&path, "[monday 2024-01-22]\n* 08:00-\n").unwrap();
+    history.commit("Second").unwrap();
+
+    history.restore("HEAD~1", "2024-W04.txt").unwrap();
+
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-12:00\n",
+        fs::read_to_string(&path).unwrap()
+    );
+    let log = history.log(None).unwrap();
+    assert_eq!(3, log.lines().count());
+    assert!(
+        log.lines()
+            .next()
+            .unwrap()
+            .ends_with("Restore 2024-W04.txt from HEAD~1")
+    );
+}
+
+#[test]
+fn revisions_that_look_like_options_are_refused() {
+    let tempdir = TempDir::new().unwrap();
+    let history = History::new(tempdir.path());
+    let path = tempdir.path().join("2024-W04.txt");
+
+    fs::write(&path, "[monday 2024-01-22]\n").unwrap();
+    history.commit("First").unwrap();
+
+    assert!(history.restore("--orphan=evil", "2024-W04.txt").is_err());
+    assert!(history.restore("nonexistent", "2024-W04.txt").is_err());
+    assert_eq!(1, history.log(None).unwrap().lines().count());
+}
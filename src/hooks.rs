@@ -0,0 +1,105 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde::Serialize;
+
+/// Something that happened, passed on to a hook
+#[derive(Debug, Serialize)]
+pub struct HookEvent {
+    pub event: &'static str,
+    #[serde(serialize_with = "serialize_date")]
+    pub date: NaiveDate,
+    #[serde(serialize_with = "serialize_time")]
+    pub time: NaiveTime,
+    /// Length of the shift, in minutes, when it has been stopped
+    pub shift_minutes: Option<i64>,
+    /// Balance after the event, in minutes
+    pub balance_minutes: i64,
+}
+
+impl HookEvent {
+    pub fn new(
+        event: &'static str,
+        date: NaiveDate,
+        time: NaiveTime,
+        shift_duration: Option<Duration>,
+        balance: Duration,
+    ) -> Self {
+        HookEvent {
+            event,
+            date,
+            time,
+            shift_minutes: shift_duration.map(|duration| duration.num_minutes()),
+            balance_minutes: balance.num_minutes(),
+        }
+    }
+
+    fn environment(&self) -> Vec<(&'static str, String)> {
+        let mut environment = vec![
+            ("TRACKER_EVENT", String::from(self.event)),
+            ("TRACKER_DATE", self.date.to_string()),
+            ("TRACKER_TIME", self.time.format("%H:%M").to_string()),
+            ("TRACKER_BALANCE_MINUTES", self.balance_minutes.to_string()),
+        ];
+        if let Some(shift_minutes) = self.shift_minutes {
+            environment.push(("TRACKER_SHIFT_MINUTES", shift_minutes.to_string()));
+        }
+        environment
+    }
+}
+
+fn serialize_date<S: serde::Serializer>(
+    date: &NaiveDate,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&date.to_string())
+}
+
+fn serialize_time<S: serde::Serializer>(
+    time: &NaiveTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.format("%H:%M").to_string())
+}
+
+#[derive(Debug)]
+pub enum HookError {
+    Run(io::Error),
+    Failed(Option<i32>),
+}
+
+impl std::fmt::Display for HookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookError::Run(err) => write!(f, "could not run hook: {}", err),
+            HookError::Failed(Some(code)) => write!(f, "hook exited with status {}", code),
+            HookError::Failed(None) => write!(f, "hook was terminated by a signal"),
+        }
+    }
+}
+
+/// Runs a hook with the shell, with the event in environment variables and as JSON on stdin
+pub fn run_hook(command: &str, event: &HookEvent) -> Result<(), HookError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(event.environment())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(HookError::Run)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_string(event).expect("hook events can always be serialized");
+        // The hook may well not care about stdin and exit without reading it
+        let _ = writeln!(stdin, "{}", json);
+    }
+    let status = child.wait().map_err(HookError::Run)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(HookError::Failed(status.code()))
+    }
+}
+
+#[cfg(test)]
+mod tests;
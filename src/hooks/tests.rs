@@ -0,0 +1,56 @@
+use std::fs;
+
+use chrono::Duration;
+use temp_dir::TempDir;
+
+use crate::hooks::{HookError, HookEvent, run_hook};
+use crate::testutils::{naive_date, naive_time};
+
+fn stop_event() -> HookEvent {
+    HookEvent::new(
+        "stop",
+        naive_date(2024, 1, 22),
+        naive_time(12, 0),
+        Duration::try_hours(4),
+        Duration::minutes(-240),
+    )
+}
+
+#[test]
+fn event_is_passed_in_environment() {
+    let tempdir = TempDir::new().unwrap();
+    let output = tempdir.path().join("output");
+    let command = format!(
+        "echo \"$TRACKER_EVENT $TRACKER_DATE $TRACKER_TIME $TRACKER_SHIFT_MINUTES $TRACKER_BALANCE_MINUTES\" > {}",
+        output.display()
+    );
+
+    run_hook(&command, &stop_event()).unwrap();
+
+    assert_eq!(
+        "stop 2024-01-22 12:00 240 -240\n",
+        fs::read_to_string(&output).unwrap()
+    );
+}
+
+#[test]
+fn event_is_passed_as_json_on_stdin() {
+    let tempdir = TempDir::new().unwrap();
+    let output = tempdir.path().join("output");
+    let command = format!("cat > {}", output.display());
+
+    run_hook(&command, &stop_event()).unwrap();
+
+    assert_eq!(
+        "{\"event\":\"stop\",\"date\":\"2024-01-22\",\"time\":\"12:00\",\"shift_minutes\":240,\"balance_minutes\":-240}\n",
+        fs::read_to_string(&output).unwrap()
+    );
+}
+
+#[test]
+fn failing_hook_is_an_error() {
+    assert!(matches!(
+        run_hook("exit 3", &stop_event()),
+        Err(HookError::Failed(Some(3)))
+    ));
+}
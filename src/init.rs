@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::Weekday;
+
+use crate::constants;
+use crate::locale::Locale;
+
+/// What to put in a new config file. Anything not given is asked for, or left at its
+/// default when not asking.
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub hours_per_day: Option<u32>,
+    pub days_per_week: Option<u32>,
+    pub week_start: Option<Weekday>,
+    pub locale: Option<Locale>,
+    pub data_dir: Option<PathBuf>,
+    /// Replace an existing config file
+    pub force: bool,
+    /// Ask for what was not given
+    pub interactive: bool,
+}
+
+/// The settings written by `tracker init`
+#[derive(Debug, PartialEq)]
+pub(crate) struct InitAnswers {
+    pub hours_per_day: u32,
+    pub days_per_week: u32,
+    pub week_start: Weekday,
+    pub locale: Locale,
+    /// Where week files are kept, if not in the default location
+    pub data_dir: Option<PathBuf>,
+}
+
+impl InitAnswers {
+    /// The answers from the options, asking for anything missing if interactive
+    pub fn from_options(options: &InitOptions) -> Self {
+        InitAnswers {
+            hours_per_day: given_or_asked(
+                options.hours_per_day,
+                options.interactive,
+                "Hours per work day",
+                constants::DEFAULT_WORK_HOURS_PER_DAY,
+            ),
+            days_per_week: given_or_asked(
+                options.days_per_week,
+                options.interactive,
+                "Work days per week",
+                constants::DEFAULT_WORK_DAYS_PER_WEEK,
+            ),
+            week_start: given_or_asked(
+                options.week_start,
+                options.interactive,
+                "First day of the week",
+                Weekday::Mon,
+            ),
+            locale: given_or_asked(
+                options.locale,
+                options.interactive,
+                "Language of day headers and reports (en or sv)",
+                Locale::English,
+            ),
+            data_dir: Some(given_or_asked(
+                options
+                    .data_dir
+                    .as_ref()
+                    .map(|data_dir| data_dir.display().to_string()),
+                options.interactive,
+                "Where to keep week files (leave empty for the default location)",
+                String::new(),
+            ))
+            .filter(|data_dir| !data_dir.is_empty())
+            .map(PathBuf::from),
+        }
+    }
+
+    /// A config file with these settings, and comments on what they mean
+    pub fn config_text(&self) -> String {
+        let data_dir = match &self.data_dir {
+            Some(data_dir) => format!(
+                "data_dir = {}",
+                toml::Value::String(data_dir.display().to_string())
+            ),
+            None => String::from("# data_dir = \"~/Sync/tracker\""),
+        };
+        format!(
+            r#"# Configuration for tracker. See the README for all settings.
+
+# The language of day headers in week files and of reports, "en" or "sv"
+locale = "{locale}"
+
+[workweek]
+# The time expected to be worked each work day
+hours_per_day = {hours_per_day}
+# The number of work days, counted from monday. List them in working_days, like
+# working_days = ["sun", "mon", "tue", "wed", "thu"], to work other days.
+days_per_week = {days_per_week}
+# The day that weeks start on
+week_start = "{week_start}"
+
+[storage]
+# Where week files are kept, if not in the default location
+{data_dir}
+"#,
+            locale = self.locale.code(),
+            hours_per_day = self.hours_per_day,
+            days_per_week = self.days_per_week,
+            week_start = Locale::English.weekday_name(self.week_start),
+            data_dir = data_dir,
+        )
+    }
+}
+
+fn given_or_asked<T: FromStr + Display>(
+    given: Option<T>,
+    interactive: bool,
+    question: &str,
+    default: T,
+) -> T {
+    match (given, interactive) {
+        (Some(given), _) => given,
+        (None, true) => ask(question, &default.to_string()),
+        (None, false) => default,
+    }
+}
+
+/// Asks until the answer can be parsed, with an empty answer meaning the default
+fn ask<T: FromStr>(question: &str, default: &str) -> T {
+    loop {
+        if default.is_empty() {
+            print!("{}: ", question);
+        } else {
+            print!("{} [{}]: ", question, default);
+        }
+        io::stdout().flush().expect("Could not write to stdout");
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+            std::process::exit(1);
+        }
+        let answer = match answer.trim() {
+            "" => default,
+            answer => answer,
+        };
+        match answer.parse() {
+            Ok(value) => return value,
+            Err(_) => println!("Please answer with something like {}.", default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
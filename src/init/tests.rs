@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use chrono::Weekday;
+
+use crate::config::read_config_from_str;
+use crate::init::{InitAnswers, InitOptions};
+use crate::locale::Locale;
+
+#[test]
+fn options_not_given_are_defaults_when_not_asking() {
+    let answers = InitAnswers::from_options(&InitOptions {
+        hours_per_day: Some(6),
+        ..Default::default()
+    });
+
+    assert_eq!(
+        answers,
+        InitAnswers {
+            hours_per_day: 6,
+            days_per_week: 5,
+            week_start: Weekday::Mon,
+            locale: Locale::English,
+            data_dir: None,
+        }
+    );
+}
+
+#[test]
+fn config_text_is_a_valid_config() {
+    let answers = InitAnswers {
+        hours_per_day: 6,
+        days_per_week: 4,
+        week_start: Weekday::Sun,
+        locale: Locale::Swedish,
+        data_dir: Some(PathBuf::from("/home/me/Sync/tracker")),
+    };
+
+    let config = read_config_from_str(&answers.config_text()).unwrap();
+
+    assert_eq!(config.workweek.hours_per_day, 6);
+    assert_eq!(config.workweek.days_per_week, 4);
+    assert_eq!(config.workweek.week_start, Weekday::Sun);
+    assert_eq!(config.locale, Locale::Swedish);
+    assert_eq!(
+        config.storage.data_dir,
+        Some(PathBuf::from("/home/me/Sync/tracker"))
+    );
+}
+
+#[test]
+fn data_dir_is_left_out_when_default() {
+    let answers = InitAnswers::from_options(&InitOptions::default());
+
+    let config = read_config_from_str(&answers.config_text()).unwrap();
+
+    assert_eq!(config.storage.data_dir, None);
+}
@@ -0,0 +1,224 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The size at which the older half of the journal is dropped
+const MAX_JOURNAL_BYTES: u64 = 1024 * 1024;
+
+/// An append-only log of the changes made to week files, with snapshots of the file before
+/// and after each change, making it possible to undo and redo them. The id of the last entry
+/// is kept in a file of its own, so that appending doesn't need to read the whole journal.
+pub struct Journal {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    Operation,
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: u64,
+    pub time: String,
+    pub kind: EntryKind,
+    pub description: String,
+    pub path: PathBuf,
+    /// The contents of the file before the change, if it existed
+    pub before: Option<String>,
+    pub after: String,
+    /// The operation that an undo or redo applies to
+    pub target: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entries {
+    #[serde(default)]
+    entry: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    Invalid(toml::de::Error),
+}
+
+impl std::fmt::Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalError::Io(err) => write!(f, "{}", err),
+            JournalError::Invalid(err) => write!(f, "invalid journal: {}", err),
+        }
+    }
+}
+
+impl Journal {
+    pub fn new(path: &Path) -> Self {
+        Journal {
+            path: path.to_path_buf(),
+            max_bytes: MAX_JOURNAL_BYTES,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn entries(&self) -> Result<Vec<Entry>, JournalError> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => toml::from_str::<Entries>(&content)
+                .map(|entries| entries.entry)
+                .map_err(JournalError::Invalid),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(JournalError::Io(err)),
+        }
+    }
+
+    /// Appends an entry, giving it the next id. When the journal has grown too large, the
+    /// older half of it is dropped.
+    pub fn append(&self, mut entry: Entry) -> Result<Entry, JournalError> {
+        entry.id = self.last_id()? + 1;
+        let serialized = toml::to_string(&Entries {
+            entry: vec![entry.clone()],
+        })
+        .expect("journal entries can always be serialized");
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(JournalError::Io)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(JournalError::Io)?;
+        writeln!(file, "{}", serialized).map_err(JournalError::Io)?;
+        fs::write(self.last_id_path(), entry.id.to_string()).map_err(JournalError::Io)?;
+        if file.metadata().map_err(JournalError::Io)?.len() > self.max_bytes {
+            self.drop_older_half()?;
+        }
+        Ok(entry)
+    }
+
+    /// The id of the last entry, or 0 if there are none. Journals written before the id was
+    /// kept on its own are read in full.
+    fn last_id(&self) -> Result<u64, JournalError> {
+        match fs::read_to_string(self.last_id_path()) {
+            Ok(content) if let Ok(id) = content.trim().parse::<u64>() => Ok(id),
+            _ => Ok(self.entries()?.last().map(|last| last.id).unwrap_or(0)),
+        }
+    }
+
+    fn last_id_path(&self) -> PathBuf {
+        self.path.with_extension("last_id")
+    }
+
+    /// Rewrites the journal with only the newer half of its entries, starting at an operation
+    /// so that the undos and redos that are kept refer to operations that are kept
+    fn drop_older_half(&self) -> Result<(), JournalError> {
+        let entries = self.entries()?;
+        let Some(first_kept) = (entries.len() / 2..entries.len())
+            .find(|index| entries[*index].kind == EntryKind::Operation)
+        else {
+            return Ok(());
+        };
+        let serialized = toml::to_string(&Entries {
+            entry: entries[first_kept..].to_vec(),
+        })
+        .expect("journal entries can always be serialized");
+        let temporary = self.path.with_extension("toml.new");
+        fs::write(&temporary, serialized).map_err(JournalError::Io)?;
+        fs::rename(&temporary, &self.path).map_err(JournalError::Io)
+    }
+
+    /// The operation that `undo` would revert
+    pub fn undoable(&self) -> Result<Option<Entry>, JournalError> {
+        let entries = self.entries()?;
+        let (done, _) = replay(&entries);
+        Ok(done.last().and_then(|id| find(&entries, *id)))
+    }
+
+    /// The operation that `redo` would apply again
+    pub fn redoable(&self) -> Result<Option<Entry>, JournalError> {
+        let entries = self.entries()?;
+        let (_, undone) = replay(&entries);
+        Ok(undone.last().and_then(|id| find(&entries, *id)))
+    }
+
+    /// Ids of operations that are currently undone
+    pub fn undone(&self) -> Result<Vec<u64>, JournalError> {
+        let entries = self.entries()?;
+        Ok(replay(&entries).1)
+    }
+}
+
+/// Returns the stacks of operations that are done and undone, after all entries
+fn replay(entries: &[Entry]) -> (Vec<u64>, Vec<u64>) {
+    let mut done: Vec<u64> = Vec::new();
+    let mut undone: Vec<u64> = Vec::new();
+    for entry in entries {
+        match entry.kind {
+            EntryKind::Operation => {
+                done.push(entry.id);
+                undone.clear();
+            }
+            EntryKind::Undo => {
+                done.pop();
+                undone.extend(entry.target);
+            }
+            EntryKind::Redo => {
+                undone.pop();
+                done.extend(entry.target);
+            }
+        }
+    }
+    (done, undone)
+}
+
+fn find(entries: &[Entry], id: u64) -> Option<Entry> {
+    entries.iter().find(|entry| entry.id == id).cloned()
+}
+
+/// A simple line based diff, with lines prefixed by `-`, `+` or a space
+pub fn diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence lengths for all suffixes
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut string = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            string.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            string.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            string.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use temp_dir::TempDir;
+
+use crate::journal::{Entry, EntryKind, Journal, diff};
+
+#[test]
+fn entries_survive_a_round_trip() {
+    let tempdir = TempDir::new().unwrap();
+    let journal = Journal::new(&tempdir.path().join("journal.toml"));
+
+    journal
+        .append(entry(EntryKind::Operation, None, "a = \"quoted\"\n"))
+        .unwrap();
+    journal
+        .append(entry(EntryKind::Undo, Some(1), "[monday 2024-01-22]\n"))
+        .unwrap();
+
+    let entries = journal.entries().unwrap();
+    assert_eq!(2, entries.len());
+    assert_eq!(1, entries[0].id);
+    assert_eq!("a = \"quoted\"\n", entries[0].after);
+    assert_eq!(2, entries[1].id);
+    assert_eq!(Some(1), entries[1].target);
+}
+
+#[test]
+fn undo_and_redo_follow_the_operations() {
+    let tempdir = TempDir::new().unwrap();
+    let journal = Journal::new(&tempdir.path().join("journal.toml"));
+    assert_eq!(None, journal.undoable().unwrap());
+
+    journal
+        .append(entry(EntryKind::Operation, None, "one"))
+        .unwrap();
+    journal
+        .append(entry(EntryKind::Operation, None, "two"))
+        .unwrap();
+    assert_eq!(2, journal.undoable().unwrap().unwrap().id);
+    assert_eq!(None, journal.redoable().unwrap());
+
+    journal.append(entry(EntryKind::Undo, Some(2), "")).unwrap();
+    assert_eq!(1, journal.undoable().unwrap().unwrap().id);
+    assert_eq!(2, journal.redoable().unwrap().unwrap().id);
+
+    journal.append(entry(EntryKind::Redo, Some(2), "")).unwrap();
+    assert_eq!(2, journal.undoable().unwrap().unwrap().id);
+    assert_eq!(None, journal.redoable().unwrap());
+
+    // A new operation after an undo makes the undone operation impossible to redo
+    journal.append(entry(EntryKind::Undo, Some(2), "")).unwrap();
+    journal
+        .append(entry(EntryKind::Operation, None, "three"))
+        .unwrap();
+    assert_eq!(None, journal.redoable().unwrap());
+    assert_eq!(6, journal.undoable().unwrap().unwrap().id);
+}
+
+#[test]
+fn the_older_half_is_dropped_when_the_journal_grows_too_large() {
+    let tempdir = TempDir::new().unwrap();
+    let journal = Journal::new(&tempdir.path().join("journal.toml")).with_max_bytes(2000);
+
+    for _ in 0..20 {
+        journal
+            .append(entry(EntryKind::Operation, None, "[monday 2024-01-22]\n"))
+            .unwrap();
+    }
+
+    let entries = journal.entries().unwrap();
+    assert!(entries.len() < 20);
+    assert_eq!(20, entries.last().unwrap().id);
+    assert_eq!(
+        21,
+        journal
+            .append(entry(EntryKind::Undo, Some(20), ""))
+            .unwrap()
+            .id
+    );
+}
+
+#[test]
+fn diff_shows_changed_lines() {
+    assert_eq!(
+        "  [monday 2024-01-22]\n- * 08:00-\n+ * 08:00-12:00\n+ * 13:00-\n",
+        diff(
+            "[monday 2024-01-22]\n* 08:00-\n",
+            "[monday 2024-01-22]\n* 08:00-12:00\n* 13:00-\n"
+        )
+    );
+}
+
+fn entry(kind: EntryKind, target: Option<u64>, after: &str) -> Entry {
+    Entry {
+        id: 0,
+        time: String::from("2024-01-22 08:00:00"),
+        kind,
+        description: String::from("Test"),
+        path: PathBuf::from("/tmp/2024-W04.txt"),
+        before: None,
+        after: String::from(after),
+        target,
+    }
+}
@@ -1,10 +1,27 @@
 pub mod config;
+pub mod export;
+pub mod init;
+pub mod locale;
 pub mod paths;
 pub mod tracker;
 
+mod billing;
+mod compliance;
 mod constants;
 mod document;
+mod editor;
+mod flex;
+mod history;
+mod hooks;
+mod journal;
+mod lint;
+mod oncall;
+mod overtime;
 mod report;
+mod settings;
+mod show;
+mod supplements;
+mod timezone;
 
 #[cfg(test)]
 mod testutils;
@@ -0,0 +1,96 @@
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::document::{Document, Line};
+
+/// Something in a week file that can be parsed but is likely a mistake
+#[derive(Debug, PartialEq)]
+pub(crate) struct Warning {
+    /// The line number, counting from 1
+    pub line_number: usize,
+    pub message: String,
+}
+
+/// Warnings about day headers with the wrong weekday, days out of order or outside the
+/// week, more than one open shift, and shifts that overlap. `dates` is the first and last
+/// date of the week, if known, and `misnamed_days` are the day headers found by
+/// `Parser::misnamed_day_headers`.
+pub(crate) fn lint(
+    document: &Document,
+    dates: Option<(NaiveDate, NaiveDate)>,
+    misnamed_days: &[(usize, String)],
+) -> Vec<Warning> {
+    let mut warnings: Vec<Warning> = Vec::new();
+    let mut previous_date: Option<NaiveDate> = None;
+    let mut open_shift_seen = false;
+
+    for (day, header_line_number) in document.days.iter().zip(document.day_header_line_numbers()) {
+        if let Some((_, message)) = misnamed_days
+            .iter()
+            .find(|(line_number, _)| *line_number == header_line_number)
+        {
+            warnings.push(Warning {
+                line_number: header_line_number,
+                message: message.clone(),
+            });
+        }
+        if let Some((first, last)) = dates
+            && (day.date < first || day.date > last)
+        {
+            warnings.push(Warning {
+                line_number: header_line_number,
+                message: format!(
+                    "{} is not in this week, which is {} to {}",
+                    day.date, first, last
+                ),
+            });
+        }
+        if let Some(previous_date) = previous_date
+            && day.date <= previous_date
+        {
+            warnings.push(Warning {
+                line_number: header_line_number,
+                message: format!("{} comes after {}", day.date, previous_date),
+            });
+        }
+        previous_date = Some(day.date);
+
+        let mut shifts: Vec<(NaiveTime, Option<NaiveTime>)> = Vec::new();
+        for (index, line) in day.lines.iter().enumerate() {
+            let line_number = header_line_number + 1 + index;
+            if matches!(line, Line::OpenShift { .. }) {
+                if open_shift_seen {
+                    warnings.push(Warning {
+                        line_number,
+                        message: String::from("There is already an open shift"),
+                    });
+                }
+                open_shift_seen = true;
+            }
+            let Some(start_time) = line.start_time() else {
+                continue;
+            };
+            let stop_time = line.stop_time();
+            // Shifts ending after midnight are not checked
+            if stop_time.is_some_and(|stop_time| stop_time < start_time) {
+                continue;
+            }
+            if let Some((other_start_time, _)) = shifts.iter().find(|(other_start, other_stop)| {
+                stop_time.is_none_or(|stop_time| *other_start < stop_time)
+                    && other_stop.is_none_or(|other_stop| start_time < other_stop)
+            }) {
+                warnings.push(Warning {
+                    line_number,
+                    message: format!(
+                        "The shift overlaps the shift starting at {}",
+                        other_start_time.format("%H:%M")
+                    ),
+                });
+            }
+            shifts.push((start_time, stop_time));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests;
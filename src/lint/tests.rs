@@ -0,0 +1,72 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::document::Parser;
+use crate::lint::{Warning, lint};
+use crate::locale::Locale;
+
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+}
+
+fn lint_text(text: &str) -> Vec<Warning> {
+    let parser = Parser::new(Locale::English);
+    let document = parser.parse_document(date(22).iso_week(), text);
+    lint(
+        &document,
+        Some((date(22), date(28))),
+        &parser.misnamed_day_headers(text),
+    )
+}
+
+fn warning(line_number: usize, message: &str) -> Warning {
+    Warning {
+        line_number,
+        message: String::from(message),
+    }
+}
+
+#[test]
+fn a_tidy_week_has_no_warnings() {
+    assert_eq!(
+        lint_text(
+            "* balance 1h 0m\n\n[monday 2024-01-22]\n* 08:00-12:00\n* 13:00-17:00\n\n[tuesday 2024-01-23]\n* 22:00-06:00\n* 08:00-\n"
+        ),
+        vec![]
+    );
+}
+
+#[test]
+fn days_outside_the_week_or_out_of_order_are_warned_about() {
+    assert_eq!(
+        lint_text(
+            "[tuesday 2024-01-23]\n* 08:00-12:00\n[monday 2024-01-22]\n\n[monday 2024-01-29]\n"
+        ),
+        vec![
+            warning(3, "2024-01-22 comes after 2024-01-23"),
+            warning(
+                5,
+                "2024-01-29 is not in this week, which is 2024-01-22 to 2024-01-28"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn overlapping_and_open_shifts_are_warned_about() {
+    assert_eq!(
+        lint_text("[monday 2024-01-22]\n* 08:00-12:00\n* 11:00-13:00\n* 14:00-\n* 15:00-\n"),
+        vec![
+            warning(3, "The shift overlaps the shift starting at 08:00"),
+            warning(5, "There is already an open shift"),
+            warning(5, "The shift overlaps the shift starting at 14:00"),
+        ]
+    );
+}
+
+#[test]
+fn day_headers_with_the_wrong_weekday_are_warned_about() {
+    assert_eq!(
+        lint_text("[monday 2024-01-22]\n* 08:00-12:00\n\n[monday 2024-01-23]\n"),
+        vec![warning(4, "2024-01-23 is a tuesday")]
+    );
+}
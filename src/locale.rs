@@ -0,0 +1,177 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+/// The language of day headers written to week files and of report text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "sv")]
+    Swedish,
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+impl Locale {
+    /// The code of the locale, as written in the config file
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Swedish => "sv",
+        }
+    }
+
+    /// The name of a weekday as written in day headers, like `monday` or `måndag`
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Locale::English => [
+                "monday",
+                "tuesday",
+                "wednesday",
+                "thursday",
+                "friday",
+                "saturday",
+                "sunday",
+            ],
+            Locale::Swedish => [
+                "måndag", "tisdag", "onsdag", "torsdag", "fredag", "lördag", "söndag",
+            ],
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The abbreviated name of a weekday, as used in tables
+    pub fn short_weekday_name(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Locale::English => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            Locale::Swedish => ["mån", "tis", "ons", "tor", "fre", "lör", "sön"],
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The weekday with a certain name, ignoring case. English names are always understood,
+    /// since that is what week files were written in before there were locales.
+    pub fn parse_weekday(self, name: &str) -> Option<Weekday> {
+        let name = name.to_lowercase();
+        [self, Locale::English].into_iter().find_map(|locale| {
+            WEEKDAYS
+                .into_iter()
+                .find(|weekday| locale.weekday_name(*weekday) == name)
+        })
+    }
+
+    pub fn worked_today(self, duration: &str, is_ongoing: bool) -> String {
+        match (self, is_ongoing) {
+            (Locale::English, false) => format!("You have worked {} today.", duration),
+            (Locale::English, true) => format!("You have worked {} today, ongoing.", duration),
+            (Locale::Swedish, false) => format!("Du har arbetat {} i dag.", duration),
+            (Locale::Swedish, true) => format!("Du har arbetat {} i dag, pågående.", duration),
+        }
+    }
+
+    pub fn worked_this_week(self, duration: &str) -> String {
+        match self {
+            Locale::English => format!("You have worked {} this week.", duration),
+            Locale::Swedish => format!("Du har arbetat {} den här veckan.", duration),
+        }
+    }
+
+    pub fn worked_in_week(self, duration: &str, week: u32, year: i32) -> String {
+        match self {
+            Locale::English => {
+                format!("You have worked {} in week {} of {}.", duration, week, year)
+            }
+            Locale::Swedish => format!("Du har arbetat {} vecka {} år {}.", duration, week, year),
+        }
+    }
+
+    pub fn balance(self, duration: &str) -> String {
+        match self {
+            Locale::English => format!("Balance: {}", duration),
+            Locale::Swedish => format!("Saldo: {}", duration),
+        }
+    }
+
+    pub fn forfeited(self, duration: &str) -> String {
+        match self {
+            Locale::English => format!("Forfeited when the balance was carried over: {}", duration),
+            Locale::Swedish => format!("Förverkat när saldot fördes över: {}", duration),
+        }
+    }
+
+    pub fn overtime(self, week: &str, bank: &str) -> String {
+        match self {
+            Locale::English => format!("Overtime: {} this week, overtime bank: {}", week, bank),
+            Locale::Swedish => format!("Övertid: {} den här veckan, övertidsbank: {}", week, bank),
+        }
+    }
+
+    pub fn on_call(self, standby: &str, incidents: &str, credited: &str) -> String {
+        match self {
+            Locale::English => format!(
+                "On call: {} standby, {} incident work, {} credited for standby",
+                standby, incidents, credited
+            ),
+            Locale::Swedish => format!(
+                "Jour: {} beredskap, {} arbete under jour, {} tillgodoräknat för beredskap",
+                standby, incidents, credited
+            ),
+        }
+    }
+
+    /// Headers of the table of days: date, worked, credited, expected and diff
+    pub fn day_table_headers(self) -> [&'static str; 5] {
+        match self {
+            Locale::English => ["Date", "Worked", "Credited", "Expected", "Diff"],
+            Locale::Swedish => ["Datum", "Arbetat", "Tillgodo", "Förväntat", "Diff"],
+        }
+    }
+
+    /// Headers of the billing table: date, kind, raw and rounded, and the label of the
+    /// total row
+    pub fn billing_table_headers(self) -> [&'static str; 5] {
+        match self {
+            Locale::English => ["Date", "Kind", "Raw", "Rounded", "Total"],
+            Locale::Swedish => ["Datum", "Typ", "Faktisk", "Avrundad", "Totalt"],
+        }
+    }
+
+    pub fn warnings(self) -> &'static str {
+        match self {
+            Locale::English => "Warnings:",
+            Locale::Swedish => "Varningar:",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        [Locale::English, Locale::Swedish]
+            .into_iter()
+            .find(|locale| locale.code() == string)
+            .ok_or_else(|| format!("unknown locale \"{}\", expected \"en\" or \"sv\"", string))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,28 @@
+use chrono::Weekday;
+
+use super::Locale;
+
+#[test]
+fn weekday_names_round_trip() {
+    for locale in [Locale::English, Locale::Swedish] {
+        for weekday in super::WEEKDAYS {
+            assert_eq!(
+                locale.parse_weekday(locale.weekday_name(weekday)),
+                Some(weekday)
+            );
+        }
+    }
+}
+
+#[test]
+fn swedish_weekdays_are_parsed_ignoring_case() {
+    assert_eq!(Locale::Swedish.parse_weekday("Lördag"), Some(Weekday::Sat));
+    assert_eq!(Locale::Swedish.parse_weekday("SÖNDAG"), Some(Weekday::Sun));
+}
+
+#[test]
+fn english_weekdays_are_always_understood() {
+    assert_eq!(Locale::Swedish.parse_weekday("monday"), Some(Weekday::Mon));
+    assert_eq!(Locale::English.parse_weekday("måndag"), None);
+    assert_eq!(Locale::English.parse_weekday("someday"), None);
+}
@@ -1,9 +1,13 @@
-use std::{io, path::PathBuf};
+use std::io::IsTerminal;
+use std::{env, io, path::PathBuf};
 
+use ::tracker::export::{CsvColumn, CsvOptions, DurationFormat};
+use ::tracker::init::InitOptions;
+use ::tracker::locale::Locale;
 use ::tracker::paths::TrackerDirs;
-use ::tracker::tracker::Tracker;
-use chrono::Local;
-use clap::{CommandFactory, Parser, Subcommand};
+use ::tracker::tracker::{StaleResolution, Tracker};
+use chrono::{Local, NaiveDate, Weekday};
+use clap::{Args as ClapArgs, CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
 use tracker::config;
 
@@ -19,19 +23,116 @@ struct Args {
     #[arg(short('f'), long("weekfile"), value_name = "WEEKFILE")]
     explicit_weekfile: Option<PathBuf>,
 
+    /// Use another config file [env: TRACKER_CONFIG]
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Keep week files and other data in another directory [env: TRACKER_DATA_DIR]
+    #[arg(long, value_name = "DIR", global = true)]
+    data_dir: Option<PathBuf>,
+
+    /// Use a named profile, with its own week files and work week
+    #[arg(
+        short,
+        long,
+        value_name = "PROFILE",
+        env = "TRACKER_PROFILE",
+        global = true
+    )]
+    profile: Option<String>,
+
     #[clap(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Create a config file, asking for the settings not given
+    Init {
+        /// Hours expected to be worked each work day
+        #[arg(long, value_name = "HOURS")]
+        hours_per_day: Option<u32>,
+
+        /// Number of work days per week
+        #[arg(long, value_name = "DAYS")]
+        days_per_week: Option<u32>,
+
+        /// The day that weeks start on (e.g., monday)
+        #[arg(long, value_name = "WEEKDAY")]
+        week_start: Option<Weekday>,
+
+        /// Language of day headers and reports (en or sv)
+        #[arg(long, value_name = "LOCALE")]
+        locale: Option<Locale>,
+
+        /// Where to keep week files, instead of the default location
+        #[arg(long, value_name = "DIR")]
+        data_dir: Option<PathBuf>,
+
+        /// Don't ask, use defaults for anything not given
+        #[arg(long)]
+        no_input: bool,
+
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show or change settings
+    Config {
+        #[clap(subcommand)]
+        action: ConfigAction,
+    },
     /// Start tracking
     Start {
         /// Start time in HH:MM format (e.g., 08:30)
         time: Option<String>,
+
+        #[clap(flatten)]
+        stale: StaleArgs,
     },
     /// Stop tracking
     Stop,
+    /// Cancel the current shift, as if it was never started
+    Cancel,
+    /// Start or stop being on call
+    Oncall {
+        #[clap(subcommand)]
+        action: OnCallAction,
+    },
+    /// Stop shifts that have gone past the configured limits, e.g. from cron
+    AutoStop,
+    /// Add a shift
+    Add {
+        /// Date in YYYY-MM-DD format
+        date: NaiveDate,
+        /// Start and stop time in HH:MM-HH:MM format (e.g., 08:30-12:00)
+        range: String,
+    },
+    /// Change the start or stop time of a shift
+    Amend {
+        /// Date of the shift in YYYY-MM-DD format
+        #[arg(required_unless_present = "last")]
+        date: Option<NaiveDate>,
+        /// Start time of the shift in HH:MM format
+        #[arg(required_unless_present = "last")]
+        time: Option<String>,
+        /// Amend the last shift of the week
+        #[arg(long, conflicts_with_all = ["date", "time"])]
+        last: bool,
+        /// New start time in HH:MM format
+        #[arg(long, value_name = "TIME")]
+        start: Option<String>,
+        /// New stop time in HH:MM format
+        #[arg(long, value_name = "TIME")]
+        stop: Option<String>,
+    },
+    /// Remove a shift
+    Remove {
+        /// Date of the shift in YYYY-MM-DD format
+        date: NaiveDate,
+        /// Start time of the shift in HH:MM format
+        time: String,
+    },
     /// Edit tracking file
     Edit {
         /// Only show path
@@ -43,42 +144,373 @@ enum Commands {
         /// Only report with status code whether work is ongoing
         #[arg(short, long)]
         is_working: bool,
+
+        /// Also show how each day contributed
+        #[arg(short, long)]
+        days: bool,
+
+        /// Also show the raw and rounded time to bill
+        #[arg(short, long)]
+        billing: bool,
+
+        /// Show the time of every profile instead
+        #[arg(long, conflicts_with_all = ["is_working", "days", "billing"])]
+        all_profiles: bool,
+
+        #[clap(flatten)]
+        stale: StaleArgs,
+    },
+    /// Show the tracked days of a week
+    Show {
+        /// Show a week relative to the current
+        #[arg(short, long, value_name = "WEEK", allow_negative_numbers = true)]
+        week: Option<i32>,
+
+        /// Show a single date, in YYYY-MM-DD format
+        #[arg(short, long, value_name = "DATE", conflicts_with = "week")]
+        date: Option<NaiveDate>,
+
+        /// Show one row per day
+        #[arg(short, long)]
+        compact: bool,
+    },
+    /// Undo the last change made by tracker
+    Undo,
+    /// Redo the last undone change
+    Redo,
+    /// List recent changes made by tracker
+    Journal {
+        /// Number of entries to show
+        #[arg(short('n'), long, default_value_t = 10)]
+        limit: usize,
     },
+    /// Show the history of the week files
+    History {
+        /// Only show changes to a certain week file, e.g. 2024-W04
+        #[arg(long, value_name = "WEEKFILE")]
+        week: Option<String>,
+    },
+    /// Restore a week file as it was in an earlier revision
+    Restore {
+        /// Revision to restore from, as shown by `tracker history`
+        revision: String,
+
+        /// Week file to restore, e.g. 2024-W04 (defaults to the current week)
+        #[arg(long, value_name = "WEEKFILE")]
+        week: Option<String>,
+    },
+    /// Check tracked time against working-time rules
+    Compliance {
+        /// First date to check in YYYY-MM-DD format (defaults to the start of the reference period)
+        #[arg(long, value_name = "DATE")]
+        from: Option<NaiveDate>,
+
+        /// Last date to check in YYYY-MM-DD format (defaults to today)
+        #[arg(long, value_name = "DATE")]
+        to: Option<NaiveDate>,
+    },
+    /// Show the time worked within each configured supplement window
+    Supplements {
+        /// First date to include in YYYY-MM-DD format (defaults to start of month)
+        #[arg(long, value_name = "DATE")]
+        from: Option<NaiveDate>,
+
+        /// Last date to include in YYYY-MM-DD format (defaults to end of month)
+        #[arg(long, value_name = "DATE")]
+        to: Option<NaiveDate>,
+    },
+    /// Export tracked time to other formats
+    Export {
+        #[clap(subcommand)]
+        format: ExportFormat,
+    },
+    /// Show which config file and data directory are used, and why
+    Doctor,
     /// Generate command-line completions
     Completions { shell: Shell },
 }
 
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Show the effective value of every setting and where it comes from
+    Show,
+    /// Show the effective value of a setting
+    Get {
+        /// The setting, like workweek.hours_per_day
+        key: String,
+    },
+    /// Change a setting in the config file
+    Set {
+        /// The setting, like workweek.hours_per_day
+        key: String,
+        /// The new value, like 6 or "Europe/Stockholm"
+        value: String,
+    },
+    /// Show the path of the config file
+    Path,
+}
+
+#[derive(Subcommand, Debug)]
+enum OnCallAction {
+    /// Start being on call
+    Start {
+        /// Start time in HH:MM format (e.g., 17:00)
+        time: Option<String>,
+    },
+    /// Stop being on call
+    Stop {
+        /// Stop time in HH:MM format (e.g., 08:00)
+        time: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExportFormat {
+    /// Export shifts and special days as iCalendar events, by default over the current week
+    Ics {
+        #[clap(flatten)]
+        range: DateRange,
+    },
+    /// Export a timesheet with one row per shift or special day, by default over the current
+    /// month
+    Csv {
+        #[clap(flatten)]
+        range: DateRange,
+
+        /// Columns to include, in order
+        #[arg(long, value_enum, value_delimiter = ',', default_values_t = CsvColumn::defaults())]
+        columns: Vec<CsvColumn>,
+
+        /// Separate fields with tabs instead of commas
+        #[arg(long)]
+        tsv: bool,
+
+        /// How to write durations
+        #[arg(long, value_enum, default_value_t = DurationFormat::Decimal)]
+        duration_format: DurationFormat,
+
+        /// Add a row with the total duration
+        #[arg(long)]
+        totals: bool,
+
+        /// Round durations for billing, as configured, with one row per billed entry
+        #[arg(long)]
+        billing: bool,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(multiple = false)]
+struct StaleArgs {
+    /// Close a shift left open on an earlier day at this time (HH:MM)
+    #[arg(long, value_name = "TIME")]
+    close_stale: Option<String>,
+
+    /// Close a shift left open on an earlier day at the configured default end time
+    #[arg(long)]
+    close_stale_at_default: bool,
+
+    /// Discard a shift left open on an earlier day
+    #[arg(long)]
+    discard_stale: bool,
+}
+
+impl StaleArgs {
+    fn resolution(self) -> Option<StaleResolution> {
+        if let Some(time) = self.close_stale {
+            Some(StaleResolution::CloseAt(time))
+        } else if self.close_stale_at_default {
+            Some(StaleResolution::CloseAtDefault)
+        } else if self.discard_stale {
+            Some(StaleResolution::Discard)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(ClapArgs, Debug)]
+struct DateRange {
+    /// First date to include in YYYY-MM-DD format
+    #[arg(long, value_name = "DATE")]
+    from: Option<NaiveDate>,
+
+    /// Last date to include in YYYY-MM-DD format
+    #[arg(long, value_name = "DATE")]
+    to: Option<NaiveDate>,
+}
+
+fn config_error_message(err: &config::ConfigError) -> String {
+    match err {
+        config::ConfigError::OpenFile(path, err) => {
+            format!("Could not open config file at {}: {}", path.display(), err)
+        }
+        config::ConfigError::InvalidFile(path, err) => {
+            format!("Invalid config file at {}: {}", path.display(), err)
+        }
+    }
+}
+
 fn main() {
     let args = Args::parse();
-    let now = Local::now().naive_local();
-    let dirs = TrackerDirs::real();
-    let config =
-        match config::read_config_from_path(&dirs.config_dir().to_path_buf().join("config.toml")) {
-            Ok(config) => config,
-            Err(config::ConfigError::OpenFile(path, err)) => {
-                eprintln!("Could not open config file at {}: {}", path.display(), err);
-                std::process::exit(1);
-            }
-            Err(config::ConfigError::InvalidFile(path, err)) => {
-                eprintln!("Invalid config file at {}: {}", path.display(), err);
-                std::process::exit(1);
-            }
-        };
+    let local_now = Local::now();
+    let now = local_now.naive_local();
+    let dirs = TrackerDirs::real().with_config_file(
+        args.config,
+        env::var_os("TRACKER_CONFIG").map(PathBuf::from),
+    );
+    // These commands are how a broken config file or a missing profile is looked into and
+    // fixed, so they work with the defaults instead
+    let repairs_config = matches!(
+        args.command,
+        Some(Commands::Init { .. } | Commands::Config { .. } | Commands::Doctor)
+    );
+    let config = match config::read_config_from_path(dirs.config_file()) {
+        Ok(config) => config,
+        Err(err) if repairs_config => {
+            eprintln!(
+                "Warning: {}. Using the default settings.",
+                config_error_message(&err)
+            );
+            config::Config::default()
+        }
+        Err(err) => {
+            eprintln!("{}", config_error_message(&err));
+            std::process::exit(1);
+        }
+    };
+    let dirs = dirs.with_data_dir(
+        args.data_dir,
+        env::var_os("TRACKER_DATA_DIR").map(PathBuf::from),
+        config.storage.data_dir.clone(),
+    );
+
+    if let Some(profile) = &args.profile
+        && !config.profiles.contains_key(profile)
+        && !repairs_config
+    {
+        eprintln!(
+            "There is no profile named {}. Add it to the config file as [profiles.{}].",
+            profile, profile
+        );
+        std::process::exit(1);
+    }
 
     let tracker = Tracker::builder(now, dirs)
         .config(config)
+        .profile(args.profile)
+        .local_offset(*local_now.offset())
         .explicit_weekfile(args.explicit_weekfile)
         .weekdiff(args.week)
         .build();
 
     match args.command {
-        Some(Commands::Start { time }) => tracker.start_tracking(time),
+        Some(Commands::Init {
+            hours_per_day,
+            days_per_week,
+            week_start,
+            locale,
+            data_dir,
+            no_input,
+            force,
+        }) => tracker.init(InitOptions {
+            hours_per_day,
+            days_per_week,
+            week_start,
+            locale,
+            data_dir,
+            force,
+            interactive: !no_input && io::stdin().is_terminal(),
+        }),
+        Some(Commands::Config {
+            action: ConfigAction::Show,
+        }) => tracker.show_config(),
+        Some(Commands::Config {
+            action: ConfigAction::Get { key },
+        }) => tracker.get_config(&key),
+        Some(Commands::Config {
+            action: ConfigAction::Set { key, value },
+        }) => tracker.set_config(&key, &value),
+        Some(Commands::Config {
+            action: ConfigAction::Path,
+        }) => tracker.show_config_path(),
+        Some(Commands::Start { time, stale }) => tracker.start_tracking(time, stale.resolution()),
         Some(Commands::Stop) => tracker.stop_tracking(),
+        Some(Commands::Cancel) => tracker.cancel_tracking(),
+        Some(Commands::Oncall {
+            action: OnCallAction::Start { time },
+        }) => tracker.start_on_call(time),
+        Some(Commands::Oncall {
+            action: OnCallAction::Stop { time },
+        }) => tracker.stop_on_call(time),
+        Some(Commands::AutoStop) => tracker.auto_stop(),
+        Some(Commands::Add { date, range }) => tracker.add_shift(date, &range),
+        Some(Commands::Amend {
+            date,
+            time,
+            last,
+            start,
+            stop,
+        }) => {
+            let shift = match (last, date, time) {
+                (true, None, None) => None,
+                (false, Some(date), Some(time)) => Some((date, time)),
+                _ => unreachable!("a date and time are required unless --last is given"),
+            };
+            tracker.amend_shift(shift, start, stop)
+        }
+        Some(Commands::Remove { date, time }) => tracker.remove_shift(date, &time),
         Some(Commands::Edit { show_path: true }) => tracker.show_weekfile_path(),
         Some(Commands::Edit { show_path: false }) => tracker.edit_file(),
-        Some(Commands::Report { is_working }) => tracker.show_report(is_working),
+        Some(Commands::Report {
+            all_profiles: true, ..
+        }) => tracker.show_combined_report(),
+        Some(Commands::Report {
+            is_working,
+            days,
+            billing,
+            all_profiles: false,
+            stale,
+        }) => tracker.show_report(is_working, days, billing, stale.resolution()),
+        Some(Commands::Show {
+            week,
+            date,
+            compact,
+        }) => tracker.show_days(week, date, compact),
+        Some(Commands::Undo) => tracker.undo(),
+        Some(Commands::Redo) => tracker.redo(),
+        Some(Commands::Journal { limit }) => tracker.show_journal(limit),
+        Some(Commands::History { week }) => tracker.show_history(week),
+        Some(Commands::Restore { revision, week }) => tracker.restore_week_file(&revision, week),
+        Some(Commands::Compliance { from, to }) => tracker.check_compliance(from, to),
+        Some(Commands::Supplements { from, to }) => tracker.show_supplements(from, to),
+        Some(Commands::Export {
+            format: ExportFormat::Ics { range },
+        }) => tracker.export_ics(range.from, range.to),
+        Some(Commands::Export {
+            format:
+                ExportFormat::Csv {
+                    range,
+                    columns,
+                    tsv,
+                    duration_format,
+                    totals,
+                    billing,
+                },
+        }) => tracker.export_csv(
+            range.from,
+            range.to,
+            CsvOptions {
+                columns,
+                separator: if tsv { '\t' } else { ',' },
+                duration_format,
+                totals,
+                billing,
+            },
+        ),
+        Some(Commands::Doctor) => tracker.doctor(),
         Some(Commands::Completions { shell }) => generate_completions(shell),
-        None => tracker.show_report(false),
+        None => tracker.show_report(false, false, false, None),
     }
 }
 
@@ -0,0 +1,88 @@
+use crate::config::OnCallConfig;
+use crate::document::Day;
+use crate::document::Line::{self, ClosedShift, OnCall, OpenShift};
+use chrono::{Duration, NaiveDateTime, NaiveTime, TimeDelta};
+
+/// Time on call, split into standby and work on incidents
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct OnCallSummary {
+    /// Time on call that was not worked
+    pub standby: Duration,
+    /// Time worked while on call, which is part of the worked time as usual
+    pub incidents: Duration,
+    /// What the standby time is worth, which is not part of the balance
+    pub credited: Duration,
+}
+
+/// Sums up the on-call periods of some days. Periods and shifts that have not been stopped
+/// are counted until now.
+pub(crate) fn summarize(days: &[Day], now: &NaiveDateTime, oncall: &OnCallConfig) -> OnCallSummary {
+    let periods = intervals(days, now, |line| match line {
+        OnCall {
+            start_time,
+            stop_time,
+        } => Some((*start_time, *stop_time)),
+        _ => None,
+    });
+    let shifts = intervals(days, now, |line| match line {
+        ClosedShift {
+            start_time,
+            stop_time,
+        } => Some((*start_time, Some(*stop_time))),
+        OpenShift { start_time } => Some((*start_time, None)),
+        _ => None,
+    });
+
+    let on_call: Duration = periods.iter().map(|(start, stop)| *stop - *start).sum();
+    let incidents: Duration = periods
+        .iter()
+        .flat_map(|period| shifts.iter().map(move |shift| overlap(period, shift)))
+        .sum();
+    let standby = on_call - incidents;
+    OnCallSummary {
+        standby,
+        incidents,
+        credited: Duration::minutes(
+            (standby.num_minutes() as f64 * oncall.compensation_ratio).round() as i64,
+        ),
+    }
+}
+
+/// The start and stop of some lines, where a stop time before the start time is on the next
+/// day, and a stop time of 00:00 is the midnight that ends the day
+fn intervals(
+    days: &[Day],
+    now: &NaiveDateTime,
+    times: impl Fn(&Line) -> Option<(NaiveTime, Option<NaiveTime>)>,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    days.iter()
+        .flat_map(|day| {
+            day.lines
+                .iter()
+                .filter_map(&times)
+                .map(move |(start_time, stop_time)| {
+                    let start = day.date.and_time(start_time);
+                    let stop = match stop_time {
+                        Some(stop_time)
+                            if stop_time < start_time || stop_time == NaiveTime::MIN =>
+                        {
+                            (day.date + TimeDelta::try_days(1).unwrap()).and_time(stop_time)
+                        }
+                        Some(stop_time) => day.date.and_time(stop_time),
+                        None => (*now).max(start),
+                    };
+                    (start, stop)
+                })
+        })
+        .collect()
+}
+
+fn overlap(
+    (start, stop): &(NaiveDateTime, NaiveDateTime),
+    (other_start, other_stop): &(NaiveDateTime, NaiveDateTime),
+) -> Duration {
+    ((*stop).min(*other_stop) - (*start).max(*other_start)).max(Duration::zero())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,92 @@
+use chrono::Duration;
+
+use crate::config::OnCallConfig;
+use crate::document::{Day, Line};
+use crate::oncall::{OnCallSummary, summarize};
+use crate::testutils::{naive_date, naive_date_time, naive_time};
+
+fn on_call(start: (u32, u32), stop: Option<(u32, u32)>) -> Line {
+    Line::OnCall {
+        start_time: naive_time(start.0, start.1),
+        stop_time: stop.map(|(hour, minute)| naive_time(hour, minute)),
+    }
+}
+
+fn shift(start: (u32, u32), stop: (u32, u32)) -> Line {
+    Line::ClosedShift {
+        start_time: naive_time(start.0, start.1),
+        stop_time: naive_time(stop.0, stop.1),
+    }
+}
+
+#[test]
+fn incidents_are_not_standby() {
+    let days = vec![
+        Day {
+            date: naive_date(2024, 1, 22),
+            lines: vec![shift((8, 0), (16, 0)), on_call((17, 0), Some((8, 0)))],
+        },
+        Day {
+            date: naive_date(2024, 1, 23),
+            lines: vec![shift((2, 0), (3, 30)), shift((7, 30), (16, 0))],
+        },
+    ];
+
+    assert_eq!(
+        OnCallSummary {
+            standby: Duration::hours(13),
+            incidents: Duration::hours(2),
+            credited: Duration::minutes(195),
+        },
+        summarize(
+            &days,
+            &naive_date_time(2024, 1, 23, 17, 0),
+            &OnCallConfig {
+                compensation_ratio: 0.25
+            }
+        )
+    );
+}
+
+#[test]
+fn ongoing_on_call_is_counted_until_now() {
+    let days = vec![Day {
+        date: naive_date(2024, 1, 22),
+        lines: vec![on_call((17, 0), None)],
+    }];
+
+    let summary = summarize(
+        &days,
+        &naive_date_time(2024, 1, 23, 1, 0),
+        &OnCallConfig::default(),
+    );
+
+    assert_eq!(Duration::hours(8), summary.standby);
+    assert_eq!(Duration::zero(), summary.credited);
+}
+
+#[test]
+fn periods_split_per_day_add_up() {
+    let days = vec![
+        Day {
+            date: naive_date(2024, 1, 27),
+            lines: vec![on_call((17, 0), Some((0, 0)))],
+        },
+        Day {
+            date: naive_date(2024, 1, 28),
+            lines: vec![on_call((0, 0), Some((0, 0)))],
+        },
+        Day {
+            date: naive_date(2024, 1, 29),
+            lines: vec![on_call((0, 0), Some((8, 0)))],
+        },
+    ];
+
+    let summary = summarize(
+        &days,
+        &naive_date_time(2024, 1, 29, 12, 0),
+        &OnCallConfig::default(),
+    );
+
+    assert_eq!(Duration::hours(39), summary.standby);
+}
@@ -0,0 +1,65 @@
+use crate::config::{OvertimeConfig, WorkWeekConfig};
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// The time worked on a day, split into regular time and overtime
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct OvertimeSplit {
+    pub regular: Duration,
+    pub overtime: Duration,
+    pub multiplier: f64,
+}
+
+impl OvertimeSplit {
+    /// The time the overtime is worth in the overtime bank
+    pub fn banked(&self) -> Duration {
+        Duration::minutes((self.overtime.num_minutes() as f64 * self.multiplier).round() as i64)
+    }
+}
+
+/// Splits the time worked on each day of a week, given in date order. Time on days that
+/// aren't working days and on holidays is all overtime if they have their own multipliers.
+/// On other days, time beyond the daily threshold is overtime, and so is regular time
+/// beyond the weekly threshold.
+pub(crate) fn split_week(
+    days: &[(NaiveDate, Duration)],
+    workweek: &WorkWeekConfig,
+    overtime: &OvertimeConfig,
+) -> Vec<OvertimeSplit> {
+    let mut regular_this_week = Duration::zero();
+    days.iter()
+        .map(|(date, worked)| {
+            let all_overtime = |multiplier| OvertimeSplit {
+                regular: Duration::zero(),
+                overtime: *worked,
+                multiplier,
+            };
+            if let Some(multiplier) = overtime.holiday_multiplier
+                && workweek.holidays.contains(date)
+            {
+                return all_overtime(multiplier);
+            }
+            if let Some(multiplier) = overtime.weekend_multiplier
+                && !workweek.is_working_day(date.weekday())
+            {
+                return all_overtime(multiplier);
+            }
+
+            let mut regular = overtime
+                .daily_threshold
+                .map_or(*worked, |threshold| (*worked).min(threshold));
+            if let Some(threshold) = overtime.weekly_threshold {
+                let left_this_week = (threshold - regular_this_week).max(Duration::zero());
+                regular = regular.min(left_this_week);
+            }
+            regular_this_week += regular;
+            OvertimeSplit {
+                regular,
+                overtime: *worked - regular,
+                multiplier: overtime.multiplier,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
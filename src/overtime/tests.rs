@@ -0,0 +1,122 @@
+use chrono::{Duration, Weekday};
+
+use crate::config::{OvertimeConfig, WorkWeekConfig};
+use crate::overtime::{OvertimeSplit, split_week};
+use crate::testutils::naive_date;
+
+fn hours(hours: i64) -> Duration {
+    Duration::hours(hours)
+}
+
+fn overtime_and_multipliers(splits: Vec<OvertimeSplit>) -> Vec<(Duration, f64)> {
+    splits
+        .into_iter()
+        .map(|split| (split.overtime, split.multiplier))
+        .collect()
+}
+
+#[test]
+fn no_overtime_without_rules() {
+    let days = vec![(naive_date(2024, 1, 22), hours(12))];
+
+    let splits = split_week(
+        &days,
+        &WorkWeekConfig::default(),
+        &OvertimeConfig::default(),
+    );
+
+    assert_eq!(
+        vec![OvertimeSplit {
+            regular: hours(12),
+            overtime: Duration::zero(),
+            multiplier: 1.5
+        }],
+        splits
+    );
+}
+
+#[test]
+fn time_beyond_daily_and_weekly_thresholds_is_overtime() {
+    let overtime = OvertimeConfig {
+        daily_threshold: Some(hours(9)),
+        weekly_threshold: Some(hours(40)),
+        ..Default::default()
+    };
+    let days = vec![
+        (naive_date(2024, 1, 22), hours(10)),
+        (naive_date(2024, 1, 23), hours(9)),
+        (naive_date(2024, 1, 24), hours(9)),
+        (naive_date(2024, 1, 25), hours(9)),
+        (naive_date(2024, 1, 26), hours(6)),
+    ];
+
+    let splits = split_week(&days, &WorkWeekConfig::default(), &overtime);
+
+    // 36 regular hours before friday leaves 4 regular hours for friday
+    assert_eq!(
+        vec![
+            (hours(1), 1.5),
+            (hours(0), 1.5),
+            (hours(0), 1.5),
+            (hours(0), 1.5),
+            (hours(2), 1.5)
+        ],
+        overtime_and_multipliers(splits)
+    );
+}
+
+#[test]
+fn weekends_and_holidays_have_their_own_multipliers() {
+    let workweek = WorkWeekConfig {
+        holidays: vec![naive_date(2024, 12, 25)],
+        ..Default::default()
+    };
+    let overtime = OvertimeConfig {
+        daily_threshold: Some(hours(9)),
+        weekend_multiplier: Some(2.0),
+        holiday_multiplier: Some(3.0),
+        ..Default::default()
+    };
+    let days = vec![
+        (naive_date(2024, 12, 24), hours(4)),
+        (naive_date(2024, 12, 25), hours(2)),
+        (naive_date(2024, 12, 28), hours(3)),
+    ];
+
+    let splits = split_week(&days, &workweek, &overtime);
+
+    assert_eq!(
+        vec![(hours(0), 1.5), (hours(2), 3.0), (hours(3), 2.0)],
+        overtime_and_multipliers(splits.clone())
+    );
+    assert_eq!(hours(6), splits[1].banked());
+}
+
+#[test]
+fn days_off_follow_the_working_days() {
+    let workweek = WorkWeekConfig {
+        working_days: Some(vec![
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+        ]),
+        ..Default::default()
+    };
+    let overtime = OvertimeConfig {
+        weekend_multiplier: Some(2.0),
+        ..Default::default()
+    };
+    let days = vec![
+        (naive_date(2024, 1, 22), hours(3)),
+        (naive_date(2024, 1, 27), hours(8)),
+    ];
+
+    let splits = split_week(&days, &workweek, &overtime);
+
+    assert_eq!(
+        vec![(hours(3), 2.0), (hours(0), 1.5)],
+        overtime_and_multipliers(splits)
+    );
+}
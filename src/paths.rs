@@ -1,33 +1,136 @@
+use std::fmt;
 use std::path::{Path, PathBuf};
 
-use directories::ProjectDirs;
+use directories::{BaseDirs, ProjectDirs};
+
+/// Why a location is used
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    Flag(&'static str),
+    EnvVar(&'static str),
+    Setting(&'static str),
+    Default,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Flag(flag) => write!(f, "the {} flag", flag),
+            Origin::EnvVar(name) => write!(f, "the {} environment variable", name),
+            Origin::Setting(key) => write!(f, "the {} setting", key),
+            Origin::Default => write!(f, "the default location"),
+        }
+    }
+}
 
 pub struct TrackerDirs {
-    config_dir: PathBuf,
+    config_file: PathBuf,
+    config_origin: Origin,
     data_dir: PathBuf,
+    data_origin: Origin,
 }
 
 impl TrackerDirs {
     pub fn real() -> TrackerDirs {
         let proj_dirs = ProjectDirs::from("tech", "skagedal", "tracker").unwrap();
         TrackerDirs {
-            config_dir: proj_dirs.config_dir().to_path_buf(),
+            config_file: proj_dirs.config_dir().join("config.toml"),
+            config_origin: Origin::Default,
             data_dir: proj_dirs.data_dir().to_path_buf(),
+            data_origin: Origin::Default,
         }
     }
 
     pub fn fixed(path: &Path) -> TrackerDirs {
         TrackerDirs {
-            config_dir: path.join("config").to_path_buf(),
-            data_dir: path.join("data").to_path_buf(),
+            config_file: path.join("config").join("config.toml"),
+            config_origin: Origin::Default,
+            data_dir: path.join("data"),
+            data_origin: Origin::Default,
+        }
+    }
+
+    /// The same directories, but with the config file given by the first of the flag and
+    /// the environment variable that is set
+    pub fn with_config_file(self, flag: Option<PathBuf>, env: Option<PathBuf>) -> TrackerDirs {
+        let (config_file, config_origin) = first_given(
+            [
+                (flag, Origin::Flag("--config")),
+                (env, Origin::EnvVar("TRACKER_CONFIG")),
+            ],
+            (self.config_file, self.config_origin),
+        );
+        TrackerDirs {
+            config_file,
+            config_origin,
+            ..self
+        }
+    }
+
+    /// The same directories, but with data kept in the first of the flag, the environment
+    /// variable and the setting that is given
+    pub fn with_data_dir(
+        self,
+        flag: Option<PathBuf>,
+        env: Option<PathBuf>,
+        setting: Option<PathBuf>,
+    ) -> TrackerDirs {
+        let (data_dir, data_origin) = first_given(
+            [
+                (flag, Origin::Flag("--data-dir")),
+                (env, Origin::EnvVar("TRACKER_DATA_DIR")),
+                (setting, Origin::Setting("storage.data_dir")),
+            ],
+            (self.data_dir, self.data_origin),
+        );
+        TrackerDirs {
+            data_dir,
+            data_origin,
+            ..self
         }
     }
 
-    pub fn config_dir(&self) -> &Path {
-        self.config_dir.as_path()
+    pub fn config_file(&self) -> &Path {
+        self.config_file.as_path()
+    }
+
+    pub fn config_origin(&self) -> &Origin {
+        &self.config_origin
     }
 
     pub fn data_dir(&self) -> &Path {
         self.data_dir.as_path()
     }
+
+    pub fn data_origin(&self) -> &Origin {
+        &self.data_origin
+    }
 }
+
+fn first_given<const N: usize>(
+    candidates: [(Option<PathBuf>, Origin); N],
+    default: (PathBuf, Origin),
+) -> (PathBuf, Origin) {
+    candidates
+        .into_iter()
+        .find_map(|(path, origin)| path.map(|path| (expand_tilde(&path), origin)))
+        .unwrap_or(default)
+}
+
+/// Replaces a leading `~` with the home directory
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    match BaseDirs::new() {
+        Some(base_dirs) => expand_tilde_with_home(path, base_dirs.home_dir()),
+        None => path.to_path_buf(),
+    }
+}
+
+fn expand_tilde_with_home(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
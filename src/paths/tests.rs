@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+
+use crate::paths::{Origin, TrackerDirs, expand_tilde_with_home};
+
+#[test]
+fn tilde_is_expanded_to_the_home_directory() {
+    let home = Path::new("/home/me");
+
+    assert_eq!(
+        expand_tilde_with_home(Path::new("~/Sync/time"), home),
+        PathBuf::from("/home/me/Sync/time")
+    );
+    assert_eq!(
+        expand_tilde_with_home(Path::new("~"), home),
+        PathBuf::from("/home/me")
+    );
+    assert_eq!(
+        expand_tilde_with_home(Path::new("/srv/~/time"), home),
+        PathBuf::from("/srv/~/time")
+    );
+    assert_eq!(
+        expand_tilde_with_home(Path::new("~other/time"), home),
+        PathBuf::from("~other/time")
+    );
+}
+
+#[test]
+fn flag_takes_precedence_over_environment_and_setting() {
+    let dirs = TrackerDirs::fixed(Path::new("/tmp/tracker")).with_data_dir(
+        Some(PathBuf::from("/flag")),
+        Some(PathBuf::from("/env")),
+        Some(PathBuf::from("/setting")),
+    );
+
+    assert_eq!(dirs.data_dir(), Path::new("/flag"));
+    assert_eq!(dirs.data_origin(), &Origin::Flag("--data-dir"));
+}
+
+#[test]
+fn setting_is_used_when_nothing_else_is_given() {
+    let dirs = TrackerDirs::fixed(Path::new("/tmp/tracker")).with_data_dir(
+        None,
+        None,
+        Some(PathBuf::from("/setting")),
+    );
+
+    assert_eq!(dirs.data_dir(), Path::new("/setting"));
+    assert_eq!(dirs.data_origin(), &Origin::Setting("storage.data_dir"));
+}
+
+#[test]
+fn default_locations_are_kept_when_nothing_is_given() {
+    let dirs = TrackerDirs::fixed(Path::new("/tmp/tracker"))
+        .with_config_file(None, None)
+        .with_data_dir(None, None, None);
+
+    assert_eq!(
+        dirs.config_file(),
+        Path::new("/tmp/tracker/config/config.toml")
+    );
+    assert_eq!(dirs.config_origin(), &Origin::Default);
+    assert_eq!(dirs.data_dir(), Path::new("/tmp/tracker/data"));
+    assert_eq!(dirs.data_origin(), &Origin::Default);
+}
@@ -1,8 +1,32 @@
 use std::ops::{Add, Sub};
 
-use crate::config::WorkWeekConfig;
+use crate::config::{Config, LimitsConfig, OnCallConfig, OvertimeConfig, WorkWeekConfig};
 use crate::document::{Day, Document, Line};
-use chrono::{Duration, IsoWeek, NaiveDate, NaiveDateTime, TimeDelta};
+use crate::oncall::{self, OnCallSummary};
+use crate::overtime::split_week;
+use crate::timezone::elapsed;
+use chrono::{Datelike, Duration, IsoWeek, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+/// The parts of the configuration that reports depend on, with the work week of a profile
+#[derive(Clone, Copy)]
+pub struct ReportSettings<'a> {
+    workweek: &'a WorkWeekConfig,
+    limits: &'a LimitsConfig,
+    overtime: &'a OvertimeConfig,
+    oncall: &'a OnCallConfig,
+}
+
+impl<'a> ReportSettings<'a> {
+    /// The settings for a profile, or for the default profile if `None`
+    pub fn new(config: &'a Config, profile: Option<&str>) -> Self {
+        ReportSettings {
+            workweek: config.workweek_for(profile),
+            limits: &config.limits,
+            overtime: &config.overtime,
+            oncall: &config.oncall,
+        }
+    }
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Report {
@@ -10,10 +34,39 @@ pub struct Report {
     pub duration_week: Duration,
     pub is_ongoing: bool,
     pub balance: Duration,
+    /// Overtime worked this week, which is not part of the balance
+    pub overtime: Duration,
+    /// The overtime bank, including what was transferred from earlier weeks
+    pub overtime_bank: Duration,
+    /// Balance lost when it was carried over to this week
+    pub forfeited: Duration,
+    /// Time on call this week
+    pub on_call: OnCallSummary,
+    pub days: Vec<DayReport>,
 }
 
-fn duration_for_line(
+/// How a single day contributed to the report
+#[derive(PartialEq, Debug, Clone)]
+pub struct DayReport {
+    pub date: NaiveDate,
+    /// Time from regular shifts
+    pub worked: Duration,
+    /// Time from special days and shifts, such as vacation
+    pub credited: Duration,
+    pub expected: Duration,
+    /// The part of the worked time that is overtime
+    pub overtime: Duration,
+    /// What the overtime is worth in the overtime bank
+    pub banked: Duration,
+    /// Worked and credited time minus expected time and overtime
+    pub diff: Duration,
+    /// The duration of each shift or special day
+    pub lines: Vec<(Line, Duration)>,
+}
+
+pub(crate) fn duration_for_line(
     line: &Line,
+    day: &Day,
     now: Option<NaiveDateTime>,
     workweek: &WorkWeekConfig,
 ) -> Duration {
@@ -21,49 +74,249 @@ fn duration_for_line(
         Line::ClosedShift {
             start_time,
             stop_time,
-        } => stop_time.signed_duration_since(*start_time),
+        } => elapsed(day, *start_time, *stop_time, workweek),
         Line::OpenShift { start_time } => now
-            .map(|now| now.time().signed_duration_since(*start_time))
+            .map(|now| elapsed(day, *start_time, now.time(), workweek))
             .unwrap_or_else(Duration::zero),
         Line::SpecialShift {
             start_time,
             stop_time,
             ..
-        } => stop_time.signed_duration_since(*start_time),
+        } => elapsed(day, *start_time, *stop_time, workweek),
         Line::SpecialDay { .. } => TimeDelta::try_hours(workweek.hours_per_day.into()).unwrap(),
         _ => Duration::zero(),
     }
 }
 
+/// The time at which a shift started at `start_time` should be stopped at the latest, if
+/// there are any limits. Shifts can not continue past midnight.
+pub(crate) fn latest_stop_time(start_time: NaiveTime, limits: &LimitsConfig) -> Option<NaiveTime> {
+    let by_length =
+        limits.max_shift.map(
+            |max_shift| match start_time.overflowing_add_signed(max_shift) {
+                (stop_time, 0) => stop_time,
+                _ => NaiveTime::from_hms_opt(23, 59, 0).unwrap(),
+            },
+        );
+    let by_time_of_day = limits
+        .auto_stop_at
+        .filter(|auto_stop_at| *auto_stop_at > start_time);
+    by_length.into_iter().chain(by_time_of_day).min()
+}
+
+/// The time until which a line is counted, which is now unless it's an open shift that
+/// has gone past its limits
+fn counted_until(line: &Line, now: NaiveDateTime, limits: &LimitsConfig) -> NaiveDateTime {
+    match line {
+        Line::OpenShift { start_time } => latest_stop_time(*start_time, limits)
+            .map(|stop_time| now.date().and_time(stop_time))
+            .filter(|stop| *stop < now)
+            .unwrap_or(now),
+        _ => now,
+    }
+}
+
 fn duration_for_day(day: &Day, workweek: &WorkWeekConfig) -> Duration {
     day.lines.iter().fold(TimeDelta::zero(), |acc, line| {
-        acc + duration_for_line(line, None, workweek)
+        acc + duration_for_line(line, day, None, workweek)
     })
 }
 
-fn duration_for_today(day: &Day, now: &NaiveDateTime, workweek: &WorkWeekConfig) -> Duration {
+fn duration_for_today(
+    day: &Day,
+    now: &NaiveDateTime,
+    workweek: &WorkWeekConfig,
+    limits: &LimitsConfig,
+) -> Duration {
     day.lines.iter().fold(TimeDelta::zero(), |acc, line| {
-        acc + duration_for_line(line, Some(*now), workweek)
+        acc + duration_for_line(line, day, Some(counted_until(line, *now, limits)), workweek)
     })
 }
 
-fn expected_days_worked(week: IsoWeek, now: &NaiveDateTime, workweek: &WorkWeekConfig) -> u32 {
-    let now = now.date();
-    let monday_of_week =
-        NaiveDate::from_isoywd_opt(week.year(), week.week(), chrono::Weekday::Mon).unwrap();
-    let days_since = now.signed_duration_since(monday_of_week).num_days() + 1;
-    num_traits::clamp(days_since as u32, 0, workweek.days_per_week)
+fn is_credited(line: &Line) -> bool {
+    matches!(line, Line::SpecialShift { .. } | Line::SpecialDay { .. })
+}
+
+fn report_for_day(
+    date: NaiveDate,
+    day: Option<&Day>,
+    now: &NaiveDateTime,
+    settings: &ReportSettings,
+) -> DayReport {
+    let workweek = settings.workweek;
+    let line_now = if date == now.date() { Some(*now) } else { None };
+    let lines: Vec<(Line, Duration)> = day
+        .map(|day| {
+            day.lines
+                .iter()
+                .filter(|line| {
+                    matches!(
+                        line,
+                        Line::OpenShift { .. }
+                            | Line::ClosedShift { .. }
+                            | Line::SpecialShift { .. }
+                            | Line::SpecialDay { .. }
+                    )
+                })
+                .map(|line| {
+                    let line_now = line_now.map(|now| counted_until(line, now, settings.limits));
+                    (
+                        line.clone(),
+                        duration_for_line(line, day, line_now, workweek),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let worked: Duration = lines
+        .iter()
+        .filter(|(line, _)| !is_credited(line))
+        .map(|(_, duration)| *duration)
+        .sum();
+    let credited: Duration = lines
+        .iter()
+        .filter(|(line, _)| is_credited(line))
+        .map(|(_, duration)| *duration)
+        .sum();
+    let expected = expected_duration_for_date(date, now, workweek);
+    DayReport {
+        date,
+        worked,
+        credited,
+        expected,
+        overtime: Duration::zero(),
+        banked: Duration::zero(),
+        diff: worked + credited - expected,
+        lines,
+    }
+}
+
+/// Reports for all days in the document, plus the days of the week where work was expected
+fn day_reports(
+    document: &Document,
+    now: &NaiveDateTime,
+    settings: &ReportSettings,
+) -> Vec<DayReport> {
+    let workweek = settings.workweek;
+    let mut dates: Vec<NaiveDate> = document.days.iter().map(|day| day.date).collect();
+    dates.extend(
+        workweek
+            .first_day(document.week)
+            .iter_days()
+            .take(7)
+            .filter(|date| !expected_duration_for_date(*date, now, workweek).is_zero()),
+    );
+    dates.sort();
+    dates.dedup();
+    let mut days: Vec<DayReport> = dates
+        .into_iter()
+        .map(|date| report_for_day(date, document.get_day(date), now, settings))
+        .collect();
+
+    let worked: Vec<(NaiveDate, Duration)> =
+        days.iter().map(|day| (day.date, day.worked)).collect();
+    for (day, split) in days
+        .iter_mut()
+        .zip(split_week(&worked, workweek, settings.overtime))
+    {
+        day.overtime = split.overtime;
+        day.banked = split.banked();
+        day.diff -= split.overtime;
+    }
+    days
+}
+
+/// The time expected to be worked during a week, up until now
+fn expected_duration_for_week(
+    week: IsoWeek,
+    now: &NaiveDateTime,
+    workweek: &WorkWeekConfig,
+) -> Duration {
+    workweek
+        .first_day(week)
+        .iter_days()
+        .take(7)
+        .map(|date| expected_duration_for_date(date, now, workweek))
+        .sum()
+}
+
+/// The time expected to be worked on a certain date, counting only dates up until now
+fn expected_duration_for_date(
+    date: NaiveDate,
+    now: &NaiveDateTime,
+    workweek: &WorkWeekConfig,
+) -> Duration {
+    if workweek.is_working_day(date.weekday()) && date <= now.date() {
+        TimeDelta::try_hours(workweek.hours_per_day.into()).unwrap()
+    } else {
+        Duration::zero()
+    }
+}
+
+/// Balance transferred from earlier weeks, as given in the preamble
+pub(crate) fn incoming_balance(document: &Document) -> Duration {
+    document
+        .preamble
+        .iter()
+        .filter_map(|d| match d {
+            Line::DurationShift { text, duration } if !is_overtime_bank(text) => Some(duration),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Overtime bank transferred from earlier weeks, given in the preamble like `* overtime 3h 0m`
+fn incoming_overtime_bank(document: &Document) -> Duration {
+    document
+        .preamble
+        .iter()
+        .filter_map(|d| match d {
+            Line::DurationShift { text, duration } if is_overtime_bank(text) => Some(duration),
+            _ => None,
+        })
+        .sum()
+}
+
+fn is_overtime_bank(text: &str) -> bool {
+    text.eq_ignore_ascii_case("overtime")
+}
+
+/// Balance lost when carried over to this week, given in the preamble as a negative duration
+/// like `* forfeited -2h 30m`. It is already part of the incoming balance.
+fn forfeited(document: &Document) -> Duration {
+    -document
+        .preamble
+        .iter()
+        .filter_map(|d| match d {
+            Line::DurationShift { text, duration } if text.eq_ignore_ascii_case("forfeited") => {
+                Some(*duration)
+            }
+            _ => None,
+        })
+        .sum::<Duration>()
+}
+
+/// Formats a duration like `7:05` or `-0:30`
+pub(crate) fn format_hours_minutes(duration: &Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!(
+        "{}{}:{:02}",
+        if minutes < 0 { "-" } else { "" },
+        minutes.abs() / 60,
+        minutes.abs() % 60
+    )
 }
 
 impl Report {
     pub fn from_document(
         document: &Document,
         now: &NaiveDateTime,
-        workweek: &WorkWeekConfig,
+        settings: &ReportSettings,
     ) -> Report {
+        let workweek = settings.workweek;
         let this_day = document.days.iter().find(|day| day.date == now.date());
         let duration_today = this_day
-            .map(|day| duration_for_today(day, now, workweek))
+            .map(|day| duration_for_today(day, now, workweek, settings.limits))
             .unwrap_or_else(Duration::zero);
         let duration_week = document
             .days
@@ -74,17 +327,12 @@ impl Report {
             })
             .add(duration_today);
 
-        let expected_days_so_far = expected_days_worked(document.week, now, workweek);
         let expected_duration_so_far_week =
-            TimeDelta::try_hours((expected_days_so_far * workweek.hours_per_day).into()).unwrap();
-        let incoming_balance: Duration = document
-            .preamble
-            .iter()
-            .filter_map(|d| match d {
-                Line::DurationShift { text: _, duration } => Some(duration),
-                _ => None,
-            })
-            .sum();
+            expected_duration_for_week(document.week, now, workweek);
+        let incoming_balance = incoming_balance(document);
+        let days = day_reports(document, now, settings);
+        let overtime_week: Duration = days.iter().map(|day| day.overtime).sum();
+        let banked_week: Duration = days.iter().map(|day| day.banked).sum();
 
         Report {
             duration_today,
@@ -92,7 +340,13 @@ impl Report {
             is_ongoing: this_day.map(Day::has_open_shift).unwrap_or_else(|| false),
             balance: duration_week
                 .sub(expected_duration_so_far_week)
+                .sub(overtime_week)
                 .add(incoming_balance),
+            overtime: overtime_week,
+            overtime_bank: incoming_overtime_bank(document).add(banked_week),
+            forfeited: forfeited(document),
+            on_call: oncall::summarize(&document.days, now, settings.oncall),
+            days,
         }
     }
 }
@@ -1,9 +1,9 @@
 use chrono::{Datelike, NaiveDate};
 
 use crate::{
-    config::WorkWeekConfig,
+    config::{Config, LimitsConfig, OnCallConfig, OvertimeConfig, WorkWeekConfig},
     document::{Day, Document, Line},
-    report::Report,
+    report::{DayReport, Report, ReportSettings, latest_stop_time},
     testutils::{iso_date, iso_week, naive_date, naive_date_time, naive_time},
 };
 
@@ -12,14 +12,20 @@ fn empty_report() {
     let now = naive_date_time(2023, 12, 18, 12, 0);
     let week = iso_week(2023, 51);
     let document = Document::new(week, vec![], vec![]);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::hours(0),
             duration_week: chrono::Duration::hours(0),
             is_ongoing: false,
-            balance: chrono::Duration::hours(-8)
+            balance: chrono::Duration::hours(-8),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
 
@@ -37,14 +43,20 @@ fn simple_report() {
         }],
     );
     let now = naive_date_time(2023, 12, 18, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::hours(4),
             duration_week: chrono::Duration::hours(4),
             is_ongoing: false,
-            balance: chrono::Duration::hours(-4)
+            balance: chrono::Duration::hours(-4),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
 
@@ -61,14 +73,20 @@ fn special_days_are_counted() {
         }],
     );
     let now = naive_date_time(2023, 12, 18, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::hours(8),
             duration_week: chrono::Duration::hours(8),
             is_ongoing: false,
-            balance: chrono::Duration::hours(0)
+            balance: chrono::Duration::hours(0),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
 
@@ -87,14 +105,20 @@ fn special_shifts_are_counted() {
         }],
     );
     let now = naive_date_time(2023, 12, 18, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::minutes(40),
             duration_week: chrono::Duration::minutes(40),
             is_ongoing: false,
-            balance: chrono::Duration::minutes(40 - 8 * 60)
+            balance: chrono::Duration::minutes(40 - 8 * 60),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
 
@@ -128,14 +152,20 @@ fn shifts_are_summed_correctly() {
         ],
     );
     let now = naive_date_time(2023, 12, 19, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::hours(4),
             duration_week: chrono::Duration::minutes(285),
             is_ongoing: false,
-            balance: chrono::Duration::minutes(285 - 2 * 8 * 60)
+            balance: chrono::Duration::minutes(285 - 2 * 8 * 60),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
 
@@ -163,13 +193,354 @@ fn report_for_earlier_week() {
     );
     // Next week, on wednesday, we're viewing the report.
     let now = naive_date_time(2023, 12, 20, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
     assert_eq!(
         Report {
             duration_today: chrono::Duration::hours(0),
             duration_week: chrono::Duration::hours(40),
             is_ongoing: false,
-            balance: chrono::Duration::hours(0)
+            balance: chrono::Duration::hours(0),
+            ..report.clone()
         },
-        Report::from_document(&document, &now, &WorkWeekConfig::default())
+        report
     )
 }
+
+#[test]
+fn days_are_broken_down() {
+    let document = Document::new(
+        iso_week(2023, 51),
+        vec![],
+        vec![
+            Day {
+                date: naive_date(2023, 12, 18), // a monday
+                lines: vec![
+                    Line::ClosedShift {
+                        start_time: naive_time(8, 0),
+                        stop_time: naive_time(12, 0),
+                    },
+                    Line::SpecialShift {
+                        text: String::from("VAB"),
+                        start_time: naive_time(13, 0),
+                        stop_time: naive_time(15, 0),
+                    },
+                    Line::Comment {
+                        text: String::from("Sick kid"),
+                    },
+                ],
+            },
+            Day {
+                date: naive_date(2023, 12, 20), // a wednesday
+                lines: vec![Line::OpenShift {
+                    start_time: naive_time(9, 0),
+                }],
+            },
+        ],
+    );
+    let now = naive_date_time(2023, 12, 20, 12, 30);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
+
+    assert_eq!(
+        vec![
+            DayReport {
+                date: naive_date(2023, 12, 18),
+                worked: chrono::Duration::hours(4),
+                credited: chrono::Duration::hours(2),
+                expected: chrono::Duration::hours(8),
+                overtime: chrono::Duration::zero(),
+                banked: chrono::Duration::zero(),
+                diff: chrono::Duration::hours(-2),
+                lines: vec![
+                    (
+                        Line::ClosedShift {
+                            start_time: naive_time(8, 0),
+                            stop_time: naive_time(12, 0),
+                        },
+                        chrono::Duration::hours(4)
+                    ),
+                    (
+                        Line::SpecialShift {
+                            text: String::from("VAB"),
+                            start_time: naive_time(13, 0),
+                            stop_time: naive_time(15, 0),
+                        },
+                        chrono::Duration::hours(2)
+                    ),
+                ],
+            },
+            DayReport {
+                date: naive_date(2023, 12, 19),
+                worked: chrono::Duration::zero(),
+                credited: chrono::Duration::zero(),
+                expected: chrono::Duration::hours(8),
+                overtime: chrono::Duration::zero(),
+                banked: chrono::Duration::zero(),
+                diff: chrono::Duration::hours(-8),
+                lines: vec![],
+            },
+            DayReport {
+                date: naive_date(2023, 12, 20),
+                worked: chrono::Duration::minutes(210),
+                credited: chrono::Duration::zero(),
+                expected: chrono::Duration::hours(8),
+                overtime: chrono::Duration::zero(),
+                banked: chrono::Duration::zero(),
+                diff: chrono::Duration::minutes(210 - 8 * 60),
+                lines: vec![(
+                    Line::OpenShift {
+                        start_time: naive_time(9, 0),
+                    },
+                    chrono::Duration::minutes(210)
+                )],
+            },
+        ],
+        report.days
+    );
+}
+
+#[test]
+fn days_of_earlier_week_are_all_expected() {
+    let document = Document::new(iso_week(2023, 50), vec![], vec![]);
+    // Next week, on monday, we're viewing the report.
+    let now = naive_date_time(2023, 12, 18, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(&Config::default(), None),
+    );
+
+    assert_eq!(5, report.days.len());
+    assert_eq!(
+        chrono::Duration::hours(-40),
+        report.days.iter().map(|day| day.diff).sum()
+    );
+}
+
+#[test]
+fn open_shift_is_capped_by_limits() {
+    let document = Document::new(
+        iso_week(2023, 51),
+        vec![],
+        vec![Day {
+            date: naive_date(2023, 12, 18),
+            lines: vec![Line::OpenShift {
+                start_time: naive_time(8, 0),
+            }],
+        }],
+    );
+    let now = naive_date_time(2023, 12, 18, 22, 0);
+    let limits = LimitsConfig {
+        max_shift: chrono::Duration::try_hours(10),
+        auto_stop_at: None,
+    };
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                limits,
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(chrono::Duration::hours(10), report.duration_today);
+
+    let limits = LimitsConfig {
+        max_shift: chrono::Duration::try_hours(10),
+        auto_stop_at: Some(naive_time(16, 30)),
+    };
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                limits,
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(chrono::Duration::minutes(510), report.duration_today);
+}
+
+#[test]
+fn latest_stop_time_does_not_pass_midnight() {
+    let limits = LimitsConfig {
+        max_shift: chrono::Duration::try_hours(10),
+        auto_stop_at: Some(naive_time(18, 30)),
+    };
+    assert_eq!(
+        Some(naive_time(23, 59)),
+        latest_stop_time(naive_time(20, 0), &limits)
+    );
+    assert_eq!(
+        Some(naive_time(18, 30)),
+        latest_stop_time(naive_time(9, 0), &limits)
+    );
+}
+
+#[test]
+fn overtime_is_moved_from_balance_to_bank() {
+    let document = Document::new(
+        iso_week(2023, 51),
+        vec![
+            Line::DurationShift {
+                text: String::from("balance"),
+                duration: chrono::Duration::hours(1),
+            },
+            Line::DurationShift {
+                text: String::from("overtime"),
+                duration: chrono::Duration::hours(3),
+            },
+        ],
+        vec![Day {
+            date: naive_date(2023, 12, 18),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(7, 0),
+                stop_time: naive_time(17, 0),
+            }],
+        }],
+    );
+    let now = naive_date_time(2023, 12, 18, 18, 0);
+    let overtime = OvertimeConfig {
+        daily_threshold: chrono::Duration::try_hours(9),
+        ..Default::default()
+    };
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                overtime,
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(
+        Report {
+            duration_week: chrono::Duration::hours(10),
+            balance: chrono::Duration::hours(2),
+            overtime: chrono::Duration::hours(1),
+            overtime_bank: chrono::Duration::minutes(270),
+            ..report.clone()
+        },
+        report
+    );
+    assert_eq!(chrono::Duration::hours(1), report.days[0].diff);
+}
+
+#[test]
+fn standby_does_not_count_towards_the_balance() {
+    let document = Document::new(
+        iso_week(2023, 51),
+        vec![],
+        vec![Day {
+            date: naive_date(2023, 12, 18),
+            lines: vec![
+                Line::ClosedShift {
+                    start_time: naive_time(8, 0),
+                    stop_time: naive_time(16, 0),
+                },
+                Line::OnCall {
+                    start_time: naive_time(16, 0),
+                    stop_time: Some(naive_time(20, 0)),
+                },
+            ],
+        }],
+    );
+    let now = naive_date_time(2023, 12, 18, 21, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                oncall: OnCallConfig {
+                    compensation_ratio: 0.5,
+                },
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(chrono::Duration::zero(), report.balance);
+    assert_eq!(chrono::Duration::hours(4), report.on_call.standby);
+    assert_eq!(chrono::Duration::hours(2), report.on_call.credited);
+}
+
+#[test]
+fn shift_across_daylight_saving_change_counts_real_time() {
+    // Clocks went from 03:00 back to 02:00 on this sunday
+    let document = Document::new(
+        iso_week(2024, 43),
+        vec![],
+        vec![Day {
+            date: naive_date(2024, 10, 27),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(0, 0),
+                stop_time: naive_time(6, 0),
+            }],
+        }],
+    );
+    let now = naive_date_time(2024, 10, 27, 12, 0);
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                workweek: WorkWeekConfig {
+                    time_zone: Some(chrono_tz::Europe::Stockholm),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(chrono::Duration::hours(7), report.duration_today);
+    assert_eq!(chrono::Duration::hours(7), report.days[5].worked);
+}
+
+#[test]
+fn expected_time_follows_week_start_and_working_days() {
+    let workweek = WorkWeekConfig {
+        week_start: chrono::Weekday::Sun,
+        working_days: Some(vec![
+            chrono::Weekday::Tue,
+            chrono::Weekday::Wed,
+            chrono::Weekday::Thu,
+            chrono::Weekday::Fri,
+            chrono::Weekday::Sat,
+        ]),
+        ..Default::default()
+    };
+    let document = Document::new(iso_week(2024, 4), vec![], vec![]);
+    let now = naive_date_time(2024, 1, 27, 18, 0); // a saturday
+    let report = Report::from_document(
+        &document,
+        &now,
+        &ReportSettings::new(
+            &Config {
+                workweek,
+                ..Default::default()
+            },
+            None,
+        ),
+    );
+    assert_eq!(chrono::Duration::hours(-40), report.balance);
+    assert_eq!(
+        (23..=27)
+            .map(|day| naive_date(2024, 1, day))
+            .collect::<Vec<_>>(),
+        report.days.iter().map(|day| day.date).collect::<Vec<_>>()
+    );
+}
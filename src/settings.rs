@@ -0,0 +1,239 @@
+use std::fmt;
+
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::config::{Config, read_config_from_str};
+
+/// A setting in the config file that can be read and changed with `tracker config`.
+/// Supplement windows and profiles are left out, they are edited in the file.
+pub(crate) struct Key {
+    /// Like `workweek.hours_per_day`
+    pub name: &'static str,
+    /// The default as written in the config file, or `None` if it is unset by default
+    pub default: Option<String>,
+}
+
+/// The names of all settings, in the order they are shown
+const NAMES: &[&str] = &[
+    "locale",
+    "workweek.days_per_week",
+    "workweek.hours_per_day",
+    "workweek.week_start",
+    "workweek.working_days",
+    "workweek.default_end_time",
+    "workweek.holidays",
+    "workweek.time_zone",
+    "experimental_features.auto_transfer_balance",
+    "storage.git",
+    "storage.data_dir",
+    "limits.max_shift",
+    "limits.auto_stop_at",
+    "hooks.pre_start",
+    "hooks.on_start",
+    "hooks.pre_stop",
+    "hooks.on_stop",
+    "hooks.on_report",
+    "billing.rounding",
+    "billing.granularity",
+    "billing.per",
+    "overtime.daily_threshold",
+    "overtime.weekly_threshold",
+    "overtime.multiplier",
+    "overtime.weekend_multiplier",
+    "overtime.holiday_multiplier",
+    "compliance.daily_rest",
+    "compliance.weekly_rest",
+    "compliance.max_average_week",
+    "compliance.reference_weeks",
+    "compliance.in_report",
+    "flex.ceiling",
+    "flex.floor",
+    "flex.period",
+    "flex.max_carry_over",
+    "oncall.compensation_ratio",
+];
+
+/// All settings, in the order they are shown, with the defaults taken from the default
+/// config
+pub(crate) fn keys() -> Vec<Key> {
+    let defaults = toml::to_string(&Config::default())
+        .expect("The default config can be written")
+        .parse::<DocumentMut>()
+        .expect("The default config is valid TOML");
+    NAMES
+        .iter()
+        .map(|&name| Key {
+            name,
+            default: get_item(&defaults, name).map(format_item),
+        })
+        .collect()
+}
+
+/// Where the effective value of a setting comes from
+#[derive(Debug, PartialEq)]
+pub(crate) enum Source {
+    Default,
+    File,
+    Env(&'static str),
+    Flag(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "file"),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::Flag(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// The effective value of a setting
+#[derive(Debug, PartialEq)]
+pub(crate) struct Setting {
+    pub key: &'static str,
+    /// The value as written in the config file, or `None` if it is not set
+    pub value: Option<String>,
+    pub source: Source,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum SettingsError {
+    /// The config file is not valid TOML
+    InvalidFile(String),
+    UnknownKey(String),
+    /// The setting would make the config invalid
+    InvalidValue(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::InvalidFile(message) => write!(f, "{}", message),
+            SettingsError::UnknownKey(key) => {
+                writeln!(f, "There is no setting named {}. The settings are:", key)?;
+                for key in keys() {
+                    writeln!(f, "  {}", key.name)?;
+                }
+                write!(
+                    f,
+                    "Supplement windows and profiles are changed by editing the config file."
+                )
+            }
+            SettingsError::InvalidValue(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+fn parse_document(text: &str) -> Result<DocumentMut, SettingsError> {
+    text.parse::<DocumentMut>()
+        .map_err(|error| SettingsError::InvalidFile(error.to_string()))
+}
+
+/// Parses a value as TOML, or takes it as a string if it isn't, so that both `8` and
+/// `Europe/Stockholm` can be given without quotes
+fn parse_value(string: &str) -> Value {
+    match string.trim().parse::<Value>() {
+        Ok(Value::Datetime(_)) | Err(_) => Value::from(string.trim()),
+        Ok(mut value) => {
+            value.decor_mut().clear();
+            value
+        }
+    }
+}
+
+fn get_item<'a>(document: &'a DocumentMut, key: &str) -> Option<&'a Item> {
+    key.split('.')
+        .try_fold(document.as_item(), |item, part| item.get(part))
+        .filter(|item| !item.is_none())
+}
+
+/// A value as written in the config file, without surrounding whitespace and comments
+fn format_value(value: &Value) -> String {
+    value.clone().decorated("", "").to_string()
+}
+
+/// A string as it would be written in the config file
+pub(crate) fn string_value(string: &str) -> String {
+    format_value(&Value::from(string))
+}
+
+fn format_item(item: &Item) -> String {
+    match item.as_value() {
+        Some(value) => format_value(value),
+        None => item.to_string().trim().to_string(),
+    }
+}
+
+fn set_item(document: &mut DocumentMut, key: &str, value: Value) {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().expect("Keys are never empty");
+    let table = parts
+        .into_iter()
+        .fold(document.as_item_mut(), |item, part| {
+            let child = &mut item[part];
+            if child.is_none() {
+                *child = toml_edit::table();
+            }
+            child
+        });
+    // Keep comments after the old value
+    let mut value = value;
+    if let Some(old) = table.get(last).and_then(Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    table[last] = Item::Value(value);
+}
+
+fn check_key(key: &str) -> Result<(), SettingsError> {
+    if keys().iter().any(|known| known.name == key) {
+        Ok(())
+    } else {
+        Err(SettingsError::UnknownKey(String::from(key)))
+    }
+}
+
+/// The value of every setting in the config file, or its default
+pub(crate) fn effective_settings(text: &str) -> Result<Vec<Setting>, SettingsError> {
+    let document = parse_document(text)?;
+    Ok(keys()
+        .into_iter()
+        .map(|key| {
+            let (value, source) = match get_item(&document, key.name) {
+                Some(item) => (Some(format_item(item)), Source::File),
+                None => (key.default, Source::Default),
+            };
+            Setting {
+                key: key.name,
+                value,
+                source,
+            }
+        })
+        .collect())
+}
+
+/// The value of a setting in the config file, or its default
+pub(crate) fn effective_setting(text: &str, key: &str) -> Result<Setting, SettingsError> {
+    check_key(key)?;
+    Ok(effective_settings(text)?
+        .into_iter()
+        .find(|setting| setting.key == key)
+        .expect("The key is known"))
+}
+
+/// The config file with a setting changed, keeping comments and formatting. Fails if the
+/// key is not known or the value is not valid for it.
+pub(crate) fn setting(text: &str, key: &str, value: &str) -> Result<String, SettingsError> {
+    check_key(key)?;
+    let mut document = parse_document(text)?;
+    set_item(&mut document, key, parse_value(value));
+    let text = document.to_string();
+    read_config_from_str(&text).map_err(|error| {
+        SettingsError::InvalidValue(format!("Invalid value for {}: {}", key, error.message()))
+    })?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests;
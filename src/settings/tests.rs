@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+
+use chrono::{Duration, Weekday};
+use toml_edit::{DocumentMut, Table};
+
+use crate::config::{
+    Config, FlexConfig, FlexPeriod, HooksConfig, LimitsConfig, OvertimeConfig, StorageConfig,
+    WorkWeekConfig, read_config_from_str,
+};
+use crate::settings::{
+    NAMES, Setting, SettingsError, Source, effective_setting, effective_settings, keys, setting,
+};
+use crate::testutils::naive_time;
+
+const CONFIG: &str = r#"# My settings
+locale = "sv"
+
+[workweek]
+hours_per_day = 6  # Part time
+"#;
+
+#[test]
+fn every_default_is_a_valid_config() {
+    for key in keys() {
+        if let Some(default) = key.default {
+            let text = setting("", key.name, &default).unwrap();
+            assert!(read_config_from_str(&text).is_ok(), "{}", key.name);
+        }
+    }
+}
+
+#[test]
+fn settings_come_from_the_file_or_the_defaults() {
+    let settings = effective_settings(CONFIG).unwrap();
+
+    let find = |key| settings.iter().find(|setting| setting.key == key).unwrap();
+    assert_eq!(
+        find("workweek.hours_per_day"),
+        &Setting {
+            key: "workweek.hours_per_day",
+            value: Some(String::from("6")),
+            source: Source::File,
+        }
+    );
+    assert_eq!(
+        find("workweek.week_start"),
+        &Setting {
+            key: "workweek.week_start",
+            value: Some(String::from("\"monday\"")),
+            source: Source::Default,
+        }
+    );
+    assert_eq!(find("workweek.time_zone").value, None);
+}
+
+#[test]
+fn getting_an_unknown_key_fails() {
+    assert_eq!(
+        effective_setting(CONFIG, "workweek.hours"),
+        Err(SettingsError::UnknownKey(String::from("workweek.hours")))
+    );
+}
+
+#[test]
+fn setting_keeps_comments() {
+    assert_eq!(
+        setting(CONFIG, "workweek.hours_per_day", "7").unwrap(),
+        r#"# My settings
+locale = "sv"
+
+[workweek]
+hours_per_day = 7  # Part time
+"#
+    );
+}
+
+#[test]
+fn setting_adds_missing_tables() {
+    assert_eq!(
+        setting(CONFIG, "workweek.time_zone", "Europe/Stockholm").unwrap(),
+        r#"# My settings
+locale = "sv"
+
+[workweek]
+hours_per_day = 6  # Part time
+time_zone = "Europe/Stockholm"
+"#
+    );
+    assert_eq!(
+        setting("", "storage.git", "true").unwrap(),
+        "[storage]\ngit = true\n"
+    );
+}
+
+#[test]
+fn setting_an_invalid_value_fails() {
+    assert!(matches!(
+        setting(CONFIG, "workweek.week_start", "someday"),
+        Err(SettingsError::InvalidValue(_))
+    ));
+    assert!(matches!(
+        setting(CONFIG, "workweek.hours_per_day", "many"),
+        Err(SettingsError::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn unknown_keys_are_listed_in_the_error() {
+    let error = setting(CONFIG, "workweek.hours", "8").unwrap_err();
+
+    assert!(error.to_string().contains("  workweek.hours_per_day\n"));
+}
+
+#[test]
+fn defaults_are_written_like_in_the_config_file() {
+    let default = |name: &str| {
+        keys()
+            .into_iter()
+            .find(|key| key.name == name)
+            .unwrap()
+            .default
+    };
+
+    assert_eq!(
+        Some(String::from("\"17:00\"")),
+        default("workweek.default_end_time")
+    );
+    assert_eq!(
+        Some(String::from("\"11h\"")),
+        default("compliance.daily_rest")
+    );
+    assert_eq!(Some(String::from("1.5")), default("overtime.multiplier"));
+    assert_eq!(
+        Some(String::from("\"monday\"")),
+        default("workweek.week_start")
+    );
+    assert_eq!(None, default("workweek.time_zone"));
+}
+
+/// The names of all values in a table, like `workweek.hours_per_day`
+fn leaf_names(table: &Table, prefix: &str, names: &mut Vec<String>) {
+    for (key, item) in table.iter() {
+        let name = format!("{}{}", prefix, key);
+        match item.as_table() {
+            Some(table) => leaf_names(table, &format!("{}.", name), names),
+            None => names.push(name),
+        }
+    }
+}
+
+#[test]
+fn every_setting_in_the_config_is_listed() {
+    // Settings that are unset by default are set here, so that they are written too
+    let config = Config {
+        workweek: WorkWeekConfig {
+            working_days: Some(vec![Weekday::Mon]),
+            time_zone: Some(chrono_tz::Europe::Stockholm),
+            ..Default::default()
+        },
+        storage: StorageConfig {
+            git: true,
+            data_dir: Some(PathBuf::from("data")),
+        },
+        limits: LimitsConfig {
+            max_shift: Some(Duration::hours(12)),
+            auto_stop_at: Some(naive_time(22, 0)),
+        },
+        hooks: HooksConfig {
+            pre_start: Some(String::from("true")),
+            on_start: Some(String::from("true")),
+            pre_stop: Some(String::from("true")),
+            on_stop: Some(String::from("true")),
+            on_report: Some(String::from("true")),
+        },
+        overtime: OvertimeConfig {
+            daily_threshold: Some(Duration::hours(9)),
+            weekly_threshold: Some(Duration::hours(45)),
+            multiplier: 1.5,
+            weekend_multiplier: Some(2.0),
+            holiday_multiplier: Some(2.0),
+        },
+        flex: FlexConfig {
+            ceiling: Some(Duration::hours(40)),
+            floor: Some(Duration::hours(-10)),
+            period: Some(FlexPeriod::Month),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let document = toml::to_string(&config)
+        .unwrap()
+        .parse::<DocumentMut>()
+        .unwrap();
+    let mut names = vec![];
+    leaf_names(document.as_table(), "", &mut names);
+    names.retain(|name| !name.starts_with("supplements") && !name.starts_with("profiles"));
+
+    let mut listed: Vec<&str> = NAMES.to_vec();
+    listed.sort();
+    names.sort();
+    assert_eq!(listed, names);
+}
@@ -0,0 +1,231 @@
+use crate::document::{Document, Line};
+use crate::report::{Report, ReportSettings, format_hours_minutes, incoming_balance};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+pub struct ShowOptions {
+    /// Show one row per day instead of one table per day
+    pub compact: bool,
+    /// Maximum number of characters per line
+    pub width: usize,
+    /// Only show this date (the balance still runs from the start of the week)
+    pub only_date: Option<NaiveDate>,
+}
+
+struct DayRow {
+    date: NaiveDate,
+    entries: Vec<(String, Duration)>,
+    breaks: Duration,
+    worked: Duration,
+    expected: Duration,
+    /// What the day adds to the balance
+    diff: Duration,
+    balance: Duration,
+}
+
+/// Render the days of a week with their shifts, totals and running balance.
+pub fn render(
+    document: &Document,
+    now: &NaiveDateTime,
+    settings: &ReportSettings,
+    options: &ShowOptions,
+) -> String {
+    let rows: Vec<DayRow> = day_rows(document, now, settings)
+        .into_iter()
+        .filter(|row| options.only_date.is_none_or(|date| row.date == date))
+        .collect();
+    if rows.is_empty() {
+        return String::from("Nothing tracked.\n");
+    }
+    if options.compact {
+        render_compact(&rows, options.width)
+    } else {
+        render_full(&rows, options.width)
+    }
+}
+
+/// All days of the report, with the balance running from the incoming balance
+fn day_rows(document: &Document, now: &NaiveDateTime, settings: &ReportSettings) -> Vec<DayRow> {
+    let report = Report::from_document(document, now, settings);
+    let mut balance = incoming_balance(document);
+    report
+        .days
+        .into_iter()
+        .map(|day| {
+            balance += day.diff;
+            DayRow {
+                date: day.date,
+                entries: day
+                    .lines
+                    .iter()
+                    .filter_map(|(line, duration)| {
+                        entry_label(line).map(|label| (label, *duration))
+                    })
+                    .collect(),
+                breaks: breaks(day.lines.iter().map(|(line, _)| line)),
+                worked: day.worked + day.credited,
+                expected: day.expected,
+                diff: day.diff,
+                balance,
+            }
+        })
+        .collect()
+}
+
+fn entry_label(line: &Line) -> Option<String> {
+    match line {
+        Line::OpenShift { start_time } => Some(format!("{}-", start_time.format("%H:%M"))),
+        Line::ClosedShift {
+            start_time,
+            stop_time,
+        } => Some(format!(
+            "{}-{}",
+            start_time.format("%H:%M"),
+            stop_time.format("%H:%M")
+        )),
+        Line::SpecialShift {
+            text,
+            start_time,
+            stop_time,
+        } => Some(format!(
+            "{} {}-{}",
+            text,
+            start_time.format("%H:%M"),
+            stop_time.format("%H:%M")
+        )),
+        Line::SpecialDay { text } => Some(text.clone()),
+        _ => None,
+    }
+}
+
+/// The sum of the gaps between consecutive shifts
+fn breaks<'a>(lines: impl Iterator<Item = &'a Line>) -> Duration {
+    let mut shifts: Vec<(NaiveTime, Option<NaiveTime>)> = lines
+        .filter_map(|line| match line {
+            Line::OpenShift { start_time } => Some((*start_time, None)),
+            Line::ClosedShift {
+                start_time,
+                stop_time,
+            }
+            | Line::SpecialShift {
+                start_time,
+                stop_time,
+                ..
+            } => Some((*start_time, Some(*stop_time))),
+            _ => None,
+        })
+        .collect();
+    shifts.sort_by_key(|(start_time, _)| *start_time);
+    shifts
+        .windows(2)
+        .filter_map(|pair| {
+            pair[0]
+                .1
+                .map(|stop_time| pair[1].0.signed_duration_since(stop_time))
+        })
+        .filter(|gap| *gap > Duration::zero())
+        .sum()
+}
+
+fn render_full(rows: &[DayRow], width: usize) -> String {
+    let mut string = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        if index > 0 {
+            string.push('\n');
+        }
+        string.push_str(&format!("{}\n", row.date.format("%A %Y-%m-%d")));
+        let label_width = row
+            .entries
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (label, duration) in &row.entries {
+            string.push_str(&format!(
+                "  {:<label_width$}  {:>6}\n",
+                label,
+                format_hours_minutes(duration)
+            ));
+        }
+        let summary = [
+            format!("Worked {}", format_hours_minutes(&row.worked)),
+            format!("Breaks {}", format_hours_minutes(&row.breaks)),
+            format!("Expected {}", format_hours_minutes(&row.expected)),
+            format!("Diff {}", format_signed(&row.diff)),
+            format!("Balance {}", format_signed(&row.balance)),
+        ];
+        string.push_str(&wrap(&summary, width, "  "));
+    }
+    string
+}
+
+fn render_compact(rows: &[DayRow], width: usize) -> String {
+    const DATE_WIDTH: usize = 14;
+    const NUMBER_WIDTH: usize = 8;
+    let shifts_width = width
+        .saturating_sub(DATE_WIDTH + 4 * (NUMBER_WIDTH + 1) + 1)
+        .max(10);
+
+    let mut string = format!(
+        "{:<DATE_WIDTH$} {:<shifts_width$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$}\n",
+        "Date", "Shifts", "Worked", "Expected", "Diff", "Balance"
+    );
+    for row in rows {
+        let shifts = row
+            .entries
+            .iter()
+            .map(|(label, _)| label.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        string.push_str(&format!(
+            "{:<DATE_WIDTH$} {:<shifts_width$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$} {:>NUMBER_WIDTH$}\n",
+            row.date.format("%a %Y-%m-%d").to_string(),
+            truncate(&shifts, shifts_width),
+            format_hours_minutes(&row.worked),
+            format_hours_minutes(&row.expected),
+            format_signed(&row.diff),
+            format_signed(&row.balance)
+        ));
+    }
+    string
+}
+
+fn format_signed(duration: &Duration) -> String {
+    if *duration > Duration::zero() {
+        format!("+{}", format_hours_minutes(duration))
+    } else {
+        format_hours_minutes(duration)
+    }
+}
+
+/// Joins items with spaces, starting a new indented line whenever the width would be exceeded
+fn wrap(items: &[String], width: usize, indent: &str) -> String {
+    let mut string = String::new();
+    let mut line = String::from(indent);
+    for item in items {
+        let separator = if line.len() > indent.len() { "   " } else { "" };
+        if line.len() > indent.len() && line.len() + separator.len() + item.len() > width {
+            string.push_str(&line);
+            string.push('\n');
+            line = format!("{}{}", indent, item);
+        } else {
+            line.push_str(separator);
+            line.push_str(item);
+        }
+    }
+    string.push_str(&line);
+    string.push('\n');
+    string
+}
+
+fn truncate(string: &str, width: usize) -> String {
+    if string.chars().count() <= width {
+        String::from(string)
+    } else {
+        let mut truncated: String = string.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests;
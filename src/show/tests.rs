@@ -0,0 +1,172 @@
+use crate::config::Config;
+use crate::document::{Day, Document, Line};
+use crate::report::{Report, ReportSettings, format_hours_minutes};
+use crate::show::{ShowOptions, render};
+use crate::testutils::{iso_week, naive_date, naive_date_time, naive_time};
+
+#[test]
+fn full_view_shows_shifts_and_running_balance() {
+    let now = naive_date_time(2024, 1, 24, 12, 0);
+    let options = ShowOptions {
+        compact: false,
+        width: 80,
+        only_date: None,
+    };
+
+    assert_eq!(
+        "Monday 2024-01-22
+  09:00-11:54    2:54
+  12:30-17:36    5:06
+  Worked 8:00   Breaks 0:36   Expected 8:00   Diff 0:00   Balance +1:00
+
+Tuesday 2024-01-23
+  Vacation    8:00
+  Worked 8:00   Breaks 0:00   Expected 8:00   Diff 0:00   Balance +1:00
+
+Wednesday 2024-01-24
+  08:00-    4:00
+  Worked 4:00   Breaks 0:00   Expected 8:00   Diff -4:00   Balance -3:00
+",
+        render(
+            &example_document(),
+            &now,
+            &ReportSettings::new(&Config::default(), None),
+            &options
+        )
+    );
+}
+
+#[test]
+fn summary_is_wrapped_to_width() {
+    let now = naive_date_time(2024, 1, 24, 12, 0);
+    let options = ShowOptions {
+        compact: false,
+        width: 40,
+        only_date: Some(naive_date(2024, 1, 23)),
+    };
+
+    assert_eq!(
+        "Tuesday 2024-01-23
+  Vacation    8:00
+  Worked 8:00   Breaks 0:00
+  Expected 8:00   Diff 0:00
+  Balance +1:00
+",
+        render(
+            &example_document(),
+            &now,
+            &ReportSettings::new(&Config::default(), None),
+            &options
+        )
+    );
+}
+
+#[test]
+fn compact_view_truncates_shifts_to_width() {
+    let now = naive_date_time(2024, 1, 24, 12, 0);
+    let options = ShowOptions {
+        compact: true,
+        width: 72,
+        only_date: None,
+    };
+
+    assert_eq!(
+        "Date           Shifts                  Worked Expected     Diff  Balance
+Mon 2024-01-22 09:00-11:54 12:30-17…     8:00     8:00     0:00    +1:00
+Tue 2024-01-23 Vacation                  8:00     8:00     0:00    +1:00
+Wed 2024-01-24 08:00-                    4:00     8:00    -4:00    -3:00
+",
+        render(
+            &example_document(),
+            &now,
+            &ReportSettings::new(&Config::default(), None),
+            &options
+        )
+    );
+}
+
+#[test]
+fn running_balance_ends_at_report_balance() {
+    let now = naive_date_time(2024, 1, 24, 12, 0);
+    let config = Config::default();
+    let settings = ReportSettings::new(&config, None);
+    let options = ShowOptions {
+        compact: true,
+        width: 80,
+        only_date: None,
+    };
+    let report = Report::from_document(&example_document(), &now, &settings);
+
+    let rendered = render(&example_document(), &now, &settings, &options);
+
+    assert!(
+        rendered
+            .trim_end()
+            .ends_with(&format_hours_minutes(&report.balance))
+    );
+}
+
+#[test]
+fn diff_leaves_out_overtime_like_the_balance() {
+    let now = naive_date_time(2024, 1, 24, 12, 0);
+    let mut config = Config::default();
+    config.overtime.daily_threshold = Some(chrono::Duration::hours(7));
+    let options = ShowOptions {
+        compact: true,
+        width: 72,
+        only_date: Some(naive_date(2024, 1, 22)),
+    };
+
+    assert_eq!(
+        "Date           Shifts                  Worked Expected     Diff  Balance
+Mon 2024-01-22 09:00-11:54 12:30-17…     8:00     8:00    -1:00     0:00
+",
+        render(
+            &example_document(),
+            &now,
+            &ReportSettings::new(&config, None),
+            &options
+        )
+    );
+}
+
+fn example_document() -> Document {
+    Document::new(
+        iso_week(2024, 4),
+        vec![Line::DurationShift {
+            text: String::from("balance"),
+            duration: chrono::Duration::hours(1),
+        }],
+        vec![
+            Day {
+                date: naive_date(2024, 1, 22),
+                lines: vec![
+                    Line::ClosedShift {
+                        start_time: naive_time(9, 0),
+                        stop_time: naive_time(11, 54),
+                    },
+                    Line::ClosedShift {
+                        start_time: naive_time(12, 30),
+                        stop_time: naive_time(17, 36),
+                    },
+                    Line::Blank,
+                ],
+            },
+            Day {
+                date: naive_date(2024, 1, 23),
+                lines: vec![
+                    Line::SpecialDay {
+                        text: String::from("Vacation"),
+                    },
+                    Line::Blank,
+                ],
+            },
+            Day {
+                date: naive_date(2024, 1, 24),
+                lines: vec![Line::OpenShift {
+                    start_time: naive_time(8, 0),
+                }],
+            },
+        ],
+    )
+}
@@ -0,0 +1,203 @@
+use crate::config::SupplementWindow;
+use crate::document::Day;
+use crate::document::Line::ClosedShift;
+use crate::report::format_hours_minutes;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+/// A part of a shift that is within one supplement window, or outside all of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: NaiveDateTime,
+    pub stop: NaiveDateTime,
+    pub category: Option<String>,
+}
+
+/// The time in each supplement category during a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaySupplements {
+    pub date: NaiveDate,
+    pub categories: Vec<(String, Duration)>,
+}
+
+/// Splits a shift into segments where the same supplement applies
+pub(crate) fn split_shift(
+    date: NaiveDate,
+    start_time: NaiveTime,
+    stop_time: NaiveTime,
+    windows: &[SupplementWindow],
+    holidays: &[NaiveDate],
+) -> Vec<Segment> {
+    let start = date.and_time(start_time);
+    // A stop time before the start time means the shift ended after midnight
+    let stop = if stop_time < start_time {
+        (date + TimeDelta::try_days(1).unwrap()).and_time(stop_time)
+    } else {
+        date.and_time(stop_time)
+    };
+
+    // Which window applies can only change at midnight or where a window starts or ends, so
+    // the category found at the start of each piece between those times holds for all of it
+    let mut boundaries = vec![start, stop];
+    let mut day = date - TimeDelta::try_days(1).unwrap();
+    while day <= stop.date() {
+        boundaries.push(day.and_time(NaiveTime::MIN));
+        for window in windows {
+            boundaries.extend(window.from.map(|from| day.and_time(from)));
+            boundaries.extend(window.to.map(|to| day.and_time(to)));
+        }
+        day += TimeDelta::try_days(1).unwrap();
+    }
+    boundaries.retain(|time| (start..=stop).contains(time));
+    boundaries.sort();
+    boundaries.dedup();
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for piece in boundaries.windows(2) {
+        let category = category_at(piece[0], windows, holidays);
+        match segments.last_mut() {
+            Some(segment) if segment.category.as_deref() == category => segment.stop = piece[1],
+            _ => segments.push(Segment {
+                start: piece[0],
+                stop: piece[1],
+                category: category.map(String::from),
+            }),
+        }
+    }
+    segments
+}
+
+/// The time in each supplement category for each day with any, in the order the categories
+/// are configured. Time is counted on the day the shift started.
+pub(crate) fn supplements_per_day(
+    days: &[Day],
+    windows: &[SupplementWindow],
+    holidays: &[NaiveDate],
+) -> Vec<DaySupplements> {
+    days.iter()
+        .filter_map(|day| {
+            let segments: Vec<Segment> = day
+                .lines
+                .iter()
+                .flat_map(|line| match line {
+                    ClosedShift {
+                        start_time,
+                        stop_time,
+                    } => split_shift(day.date, *start_time, *stop_time, windows, holidays),
+                    _ => vec![],
+                })
+                .collect();
+            let mut categories: Vec<(String, Duration)> = Vec::new();
+            for window in windows {
+                if categories
+                    .iter()
+                    .any(|(category, _)| *category == window.category)
+                {
+                    continue;
+                }
+                let duration: Duration = segments
+                    .iter()
+                    .filter(|segment| segment.category.as_ref() == Some(&window.category))
+                    .map(|segment| segment.stop - segment.start)
+                    .sum();
+                if !duration.is_zero() {
+                    categories.push((window.category.clone(), duration));
+                }
+            }
+            (!categories.is_empty()).then_some(DaySupplements {
+                date: day.date,
+                categories,
+            })
+        })
+        .collect()
+}
+
+/// A table with a row per day and a column per supplement category, ending with the totals
+pub(crate) fn render_table(days: &[DaySupplements], windows: &[SupplementWindow]) -> String {
+    let mut categories: Vec<&str> = Vec::new();
+    for window in windows {
+        if !categories.contains(&window.category.as_str()) {
+            categories.push(window.category.as_str());
+        }
+    }
+    let width = categories
+        .iter()
+        .map(|category| category.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    let mut rows: Vec<(String, Vec<Duration>)> = days
+        .iter()
+        .map(|day| {
+            let durations = categories
+                .iter()
+                .map(|category| duration_of(&day.categories, category))
+                .collect();
+            (day.date.to_string(), durations)
+        })
+        .collect();
+    let totals = (0..categories.len())
+        .map(|index| rows.iter().map(|(_, durations)| durations[index]).sum())
+        .collect();
+    rows.push((String::from("Total"), totals));
+
+    let mut table = format!("{:<10}", "Date");
+    for category in &categories {
+        table.push_str(&format!("  {:>width$}", category));
+    }
+    table.push('\n');
+    for (label, durations) in rows {
+        table.push_str(&format!("{:<10}", label));
+        for duration in durations {
+            table.push_str(&format!("  {:>width$}", format_hours_minutes(&duration)));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+fn duration_of(categories: &[(String, Duration)], category: &str) -> Duration {
+    categories
+        .iter()
+        .filter(|(name, _)| name == category)
+        .map(|(_, duration)| *duration)
+        .sum()
+}
+
+fn category_at<'a>(
+    time: NaiveDateTime,
+    windows: &'a [SupplementWindow],
+    holidays: &[NaiveDate],
+) -> Option<&'a str> {
+    windows
+        .iter()
+        .find(|window| window_contains(window, time, holidays))
+        .map(|window| window.category.as_str())
+}
+
+fn window_contains(window: &SupplementWindow, time: NaiveDateTime, holidays: &[NaiveDate]) -> bool {
+    let from = window.from.unwrap_or(NaiveTime::MIN);
+    // The day the window started on, if the time is within it
+    let start_day = match window.to {
+        Some(to) if to <= from => {
+            if time.time() >= from {
+                Some(time.date())
+            } else if time.time() < to {
+                Some(time.date() - TimeDelta::try_days(1).unwrap())
+            } else {
+                None
+            }
+        }
+        to => (time.time() >= from && to.is_none_or(|to| time.time() < to)).then_some(time.date()),
+    };
+    start_day.is_some_and(|day| {
+        window
+            .days
+            .as_ref()
+            .is_none_or(|days| days.contains(&day.weekday()))
+            && (!window.holidays || holidays.contains(&day))
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,161 @@
+use chrono::{Duration, Weekday};
+
+use crate::config::SupplementWindow;
+use crate::document::{Day, Line};
+use crate::supplements::{DaySupplements, Segment, render_table, split_shift, supplements_per_day};
+use crate::testutils::{naive_date, naive_date_time, naive_time};
+
+fn window(category: &str, from: Option<(u32, u32)>, to: Option<(u32, u32)>) -> SupplementWindow {
+    SupplementWindow {
+        category: String::from(category),
+        days: None,
+        from: from.map(|(hour, minute)| naive_time(hour, minute)),
+        to: to.map(|(hour, minute)| naive_time(hour, minute)),
+        holidays: false,
+    }
+}
+
+fn windows() -> Vec<SupplementWindow> {
+    vec![
+        SupplementWindow {
+            days: Some(vec![Weekday::Sat, Weekday::Sun]),
+            ..window("weekend", None, None)
+        },
+        SupplementWindow {
+            holidays: true,
+            ..window("holiday", None, None)
+        },
+        window("night", Some((22, 0)), Some((6, 0))),
+        SupplementWindow {
+            days: Some(vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]),
+            ..window("evening", Some((18, 0)), None)
+        },
+    ]
+}
+
+#[test]
+fn shift_is_split_at_window_boundaries() {
+    // A tuesday
+    let segments = split_shift(
+        naive_date(2024, 1, 23),
+        naive_time(16, 0),
+        naive_time(1, 30),
+        &windows(),
+        &[],
+    );
+
+    assert_eq!(
+        vec![
+            Segment {
+                start: naive_date_time(2024, 1, 23, 16, 0),
+                stop: naive_date_time(2024, 1, 23, 18, 0),
+                category: None,
+            },
+            Segment {
+                start: naive_date_time(2024, 1, 23, 18, 0),
+                stop: naive_date_time(2024, 1, 23, 22, 0),
+                category: Some(String::from("evening")),
+            },
+            Segment {
+                start: naive_date_time(2024, 1, 23, 22, 0),
+                stop: naive_date_time(2024, 1, 24, 1, 30),
+                category: Some(String::from("night")),
+            },
+        ],
+        segments
+    );
+}
+
+#[test]
+fn friday_night_continues_into_the_weekend() {
+    let segments = split_shift(
+        naive_date(2024, 1, 26),
+        naive_time(21, 0),
+        naive_time(2, 0),
+        &windows(),
+        &[],
+    );
+
+    let categories: Vec<Option<&str>> = segments
+        .iter()
+        .map(|segment| segment.category.as_deref())
+        .collect();
+    assert_eq!(
+        vec![Some("evening"), Some("night"), Some("weekend")],
+        categories
+    );
+    assert_eq!(naive_date_time(2024, 1, 27, 0, 0), segments[2].start);
+}
+
+#[test]
+fn time_per_category_and_day() {
+    let days = vec![
+        Day {
+            date: naive_date(2024, 12, 24),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(15, 0),
+                stop_time: naive_time(19, 30),
+            }],
+        },
+        Day {
+            date: naive_date(2024, 12, 25),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(10, 0),
+                stop_time: naive_time(12, 0),
+            }],
+        },
+        Day {
+            date: naive_date(2024, 12, 27),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(8, 0),
+                stop_time: naive_time(12, 0),
+            }],
+        },
+    ];
+
+    assert_eq!(
+        vec![
+            DaySupplements {
+                date: naive_date(2024, 12, 24),
+                categories: vec![(String::from("evening"), Duration::minutes(90))],
+            },
+            DaySupplements {
+                date: naive_date(2024, 12, 25),
+                categories: vec![(String::from("holiday"), Duration::hours(2))],
+            },
+        ],
+        supplements_per_day(&days, &windows(), &[naive_date(2024, 12, 25)])
+    );
+}
+
+#[test]
+fn table_has_a_column_per_category_and_totals() {
+    let days = vec![
+        DaySupplements {
+            date: naive_date(2024, 12, 24),
+            categories: vec![(String::from("evening"), Duration::minutes(90))],
+        },
+        DaySupplements {
+            date: naive_date(2024, 12, 25),
+            categories: vec![
+                (String::from("holiday"), Duration::hours(2)),
+                (String::from("night"), Duration::minutes(30)),
+            ],
+        },
+    ];
+
+    assert_eq!(
+        "Date         weekend   holiday     night   evening
+2024-12-24      0:00      0:00      0:00      1:30
+2024-12-25      0:00      2:00      0:30      0:00
+Total           0:00      2:00      0:30      1:30
+",
+        render_table(&days, &windows())
+    );
+}
@@ -0,0 +1,59 @@
+use crate::config::WorkWeekConfig;
+use crate::document::Day;
+use chrono::offset::LocalResult;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// The time that passed between two wall-clock times of a day. A stop time before the start
+/// time means that it was on the next day. If the day has no UTC offset of its own, they are
+/// in the home time zone, so that daylight saving time is taken into account.
+pub(crate) fn elapsed(
+    day: &Day,
+    start_time: NaiveTime,
+    stop_time: NaiveTime,
+    workweek: &WorkWeekConfig,
+) -> Duration {
+    let start = day.date.and_time(start_time);
+    let stop = if stop_time < start_time {
+        (day.date + Duration::days(1)).and_time(stop_time)
+    } else {
+        day.date.and_time(stop_time)
+    };
+    match (day.utc_offset(), workweek.time_zone) {
+        (None, Some(zone)) => instant(zone, stop) - instant(zone, start),
+        _ => stop - start,
+    }
+}
+
+/// The instant a wall-clock time in a time zone refers to. A time that occurs twice when the
+/// clocks are turned back is taken as the first one, and a time that is skipped when they are
+/// turned forward is taken with the offset from before.
+fn instant(zone: Tz, time: NaiveDateTime) -> DateTime<Utc> {
+    match zone.from_local_datetime(&time) {
+        LocalResult::Single(instant) | LocalResult::Ambiguous(instant, _) => {
+            instant.with_timezone(&Utc)
+        }
+        LocalResult::None => {
+            let offset = zone
+                .offset_from_utc_datetime(&(time - Duration::days(1)))
+                .fix();
+            Utc.from_utc_datetime(&(time - Duration::seconds(offset.local_minus_utc().into())))
+        }
+    }
+}
+
+/// The UTC offset to record for a day when the local clock is not in the home time zone,
+/// such as when travelling
+pub(crate) fn offset_away_from_home(
+    now: NaiveDateTime,
+    local_offset: FixedOffset,
+    workweek: &WorkWeekConfig,
+) -> Option<FixedOffset> {
+    let zone = workweek.time_zone?;
+    let utc = now - Duration::seconds(local_offset.local_minus_utc().into());
+    let home_offset = zone.offset_from_utc_datetime(&utc).fix();
+    (home_offset != local_offset).then_some(local_offset)
+}
+
+#[cfg(test)]
+mod tests;
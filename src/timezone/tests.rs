@@ -0,0 +1,93 @@
+use chrono::{Duration, FixedOffset};
+
+use crate::config::WorkWeekConfig;
+use crate::document::{Day, Line};
+use crate::testutils::{naive_date, naive_date_time, naive_time};
+use crate::timezone::{elapsed, offset_away_from_home};
+
+fn stockholm() -> WorkWeekConfig {
+    WorkWeekConfig {
+        time_zone: Some(chrono_tz::Europe::Stockholm),
+        ..Default::default()
+    }
+}
+
+fn night_shift(day: Day) -> Duration {
+    elapsed(&day, naive_time(0, 0), naive_time(6, 0), &stockholm())
+}
+
+#[test]
+fn clocks_turned_forward_shorten_the_shift() {
+    // Clocks went from 02:00 to 03:00 on this day
+    let day = Day::create(naive_date(2024, 3, 31), vec![]);
+    assert_eq!(Duration::hours(5), night_shift(day));
+}
+
+#[test]
+fn clocks_turned_back_lengthen_the_shift() {
+    // Clocks went from 03:00 back to 02:00 on this day
+    let day = Day::create(naive_date(2024, 10, 27), vec![]);
+    assert_eq!(Duration::hours(7), night_shift(day));
+}
+
+#[test]
+fn shifts_across_the_change_count_real_time() {
+    let spring = Day::create(naive_date(2024, 3, 31), vec![]);
+    assert_eq!(
+        Duration::hours(1),
+        elapsed(&spring, naive_time(1, 30), naive_time(3, 30), &stockholm())
+    );
+    // 02:30 never happened, so it is taken as if the clocks had not been turned yet
+    assert_eq!(
+        Duration::hours(1),
+        elapsed(&spring, naive_time(1, 30), naive_time(2, 30), &stockholm())
+    );
+
+    let autumn = Day::create(naive_date(2024, 10, 27), vec![]);
+    assert_eq!(
+        Duration::minutes(150),
+        elapsed(&autumn, naive_time(1, 30), naive_time(3, 0), &stockholm())
+    );
+}
+
+#[test]
+fn days_with_their_own_offset_are_not_in_the_home_zone() {
+    let day = Day::create(
+        naive_date(2024, 3, 31),
+        vec![Line::UtcOffset {
+            offset: FixedOffset::east_opt(9 * 3600).unwrap(),
+        }],
+    );
+    assert_eq!(Duration::hours(6), night_shift(day));
+}
+
+#[test]
+fn without_a_home_zone_wall_clock_time_is_used() {
+    let day = Day::create(naive_date(2024, 3, 31), vec![]);
+    assert_eq!(
+        Duration::hours(6),
+        elapsed(
+            &day,
+            naive_time(0, 0),
+            naive_time(6, 0),
+            &WorkWeekConfig::default()
+        )
+    );
+}
+
+#[test]
+fn offset_is_only_recorded_away_from_home() {
+    let summer = naive_date_time(2024, 7, 1, 9, 0);
+    let cest = FixedOffset::east_opt(2 * 3600).unwrap();
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+
+    assert_eq!(None, offset_away_from_home(summer, cest, &stockholm()));
+    assert_eq!(
+        Some(tokyo),
+        offset_away_from_home(summer, tokyo, &stockholm())
+    );
+    assert_eq!(
+        None,
+        offset_away_from_home(summer, tokyo, &WorkWeekConfig::default())
+    );
+}
@@ -1,12 +1,29 @@
-use crate::config::Config;
-use crate::document::Line::{self, OpenShift};
+use crate::billing;
+use crate::compliance::{self, Violation, WorkedShift};
+use crate::config::{Config, WorkWeekConfig};
+use crate::document::Line::{self, ClosedShift, OpenShift, SpecialShift};
 use crate::document::{Day, Document, Parser};
-use crate::paths::TrackerDirs;
-use crate::report::Report;
-use chrono::{Datelike, Duration, IsoWeek, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use crate::editor;
+use crate::export::{self, CsvOptions};
+use crate::flex;
+use crate::history::History;
+use crate::hooks::{self, HookEvent};
+use crate::init::{InitAnswers, InitOptions};
+use crate::journal::{self, Entry, EntryKind, Journal};
+use crate::lint;
+use crate::locale::Locale;
+use crate::paths::{Origin, TrackerDirs, expand_tilde};
+use crate::report::{Report, ReportSettings, format_hours_minutes, latest_stop_time};
+use crate::settings::{self, Setting, SettingsError, Source};
+use crate::show::{self, ShowOptions};
+use crate::supplements;
+use crate::timezone;
+use chrono::{
+    Datelike, Duration, FixedOffset, IsoWeek, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+};
 use std::env;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::{BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::{fs, io};
@@ -18,6 +35,28 @@ pub struct Tracker {
     now: NaiveDateTime,
     dirs: TrackerDirs,
     config: Config,
+    profile: Option<String>,
+    /// The UTC offset of the local clock
+    local_offset: Option<FixedOffset>,
+}
+
+/// How to resolve a shift that was left open on an earlier day
+#[derive(Debug, Clone)]
+pub enum StaleResolution {
+    /// Close it at a time in HH:MM format
+    CloseAt(String),
+    /// Close it at the configured default end time
+    CloseAtDefault,
+    /// Remove it, as if it was never started
+    Discard,
+}
+
+/// A shift or on-call period that has not been stopped, and the week file it is in
+struct OpenLineInFile {
+    path: PathBuf,
+    week: IsoWeek,
+    date: NaiveDate,
+    start_time: NaiveTime,
 }
 
 fn format_duration(duration: &Duration) -> String {
@@ -26,148 +65,1580 @@ fn format_duration(duration: &Duration) -> String {
     format!("{} h {} m", hours, minutes)
 }
 
+/// What to do with a week file that has problems after editing
+enum EditChoice {
+    EditAgain,
+    Save,
+    Abandon,
+}
+
+fn ask_what_now(can_save: bool) -> EditChoice {
+    if !io::stdin().is_terminal() {
+        return EditChoice::Abandon;
+    }
+    loop {
+        if can_save {
+            print!("What now? (e)dit again, (s)ave anyway or (a)bandon: ");
+        } else {
+            print!("What now? (e)dit again or (a)bandon: ");
+        }
+        io::stdout().flush().expect("Could not write to stdout");
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+            return EditChoice::Abandon;
+        }
+        match answer.trim() {
+            "e" | "" => return EditChoice::EditAgain,
+            "s" if can_save => return EditChoice::Save,
+            "a" => return EditChoice::Abandon,
+            _ => println!("Please answer with e, s or a."),
+        }
+    }
+}
+
+/// Replaces a file by writing next to it and renaming, so that it is never half written
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let temporary = path.with_file_name(format!(".{}.new", file_name(path)));
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+fn exit_with_settings_error(err: SettingsError) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
+}
+
+/// Formats a date like `Mon 2024-01-22` for tables
+fn format_table_date(date: NaiveDate, locale: Locale) -> String {
+    format!(
+        "{} {}",
+        locale.short_weekday_name(date.weekday()),
+        date.format("%Y-%m-%d")
+    )
+}
+
 impl Tracker {
-    pub fn start_tracking(&self, time_str: Option<String>) {
+    pub fn start_tracking(&self, time_str: Option<String>, stale: Option<StaleResolution>) {
+        self.resolve_stale_open_shifts(stale, true);
+        self.exit_if_tracking_in_other_profile();
+
         let date = self.now.date();
         let time = match time_str {
             Some(time_str) => self.parse_time(&time_str),
             None => self.now.time(),
         };
-        let path_buf = if self.config.experimental_features.auto_transfer_balance {
-            week_tracker_file_create_if_needed_with_transfer(
-                date.iso_week(),
-                self.week_tracker_file(date),
-                self.week_to_transfer_from(date),
-            )
-        } else {
-            week_tracker_file_create_if_needed(date.iso_week(), self.week_tracker_file(date))
+        let week = self.week_of(date);
+        let path_buf = self.week_tracker_file(date);
+        // The week file is only created once the pre_start hook has had its say
+        let document = match self.read_document(week, path_buf.as_path()) {
+            Ok(document) => document,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                if self.config.experimental_features.auto_transfer_balance {
+                    self.document_with_transfer(week, self.week_to_transfer_from(date))
+                } else {
+                    Document::empty(week)
+                }
+            }
+            Err(err) => {
+                panic!("Unexpected error reading document: {}", err);
+            }
+        };
+
+        if let Err(err) = self.validate_start_time(&document, date, time) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+
+        let document = match self.document_with_tracking_started(&document, date, time) {
+            Ok(doc) => self.with_local_offset(doc, date),
+            Err(DocumentError::TrackerFileAlreadyHasOpenShift) => {
+                eprintln!(
+                    "You are already tracking. Use `tracker stop` to end the current shift. If you forgot to stop tracking earlier, use `tracker edit`."
+                );
+                std::process::exit(1);
+            }
+            Err(_) => {
+                panic!("Unexpected error starting tracking");
+            }
+        };
+
+        let event = HookEvent::new("start", date, time, None, self.balance(&document));
+        self.run_pre_hook("pre_start", &self.config.hooks.pre_start, &event);
+
+        if let Some(parent) = path_buf.parent() {
+            fs::create_dir_all(parent).expect("Could not create directory for week files");
+        }
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path_buf.as_path(),
+            &document,
+            &format!("Start shift at {} on {}", time.format("%H:%M"), date),
+        );
+
+        self.run_post_hook("on_start", &self.config.hooks.on_start, &event);
+    }
+
+    pub fn stop_tracking(&self) {
+        let date = self.now.date();
+        let time = self.now.time();
+        let path_buf = self.week_tracker_file(date);
+        let document = match self.read_document(self.week_of(date), path_buf.as_path()) {
+            Ok(document) => document,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                println!("No tracking file for this week has been created.");
+                return;
+            }
+            Err(err) => {
+                panic!("Unexpected error reading document: {}", err);
+            }
+        };
+
+        // The same as the report counts for the shift once it is stopped
+        let shift_duration = document.open_shift().and_then(|(shift_date, start_time)| {
+            let day = document.get_day(shift_date)?;
+            Some(timezone::elapsed(day, start_time, time, self.workweek()))
+        });
+        let document = match self.document_with_tracking_stopped(&document, date, time) {
+            Ok(document) => document,
+            Err(DocumentError::TrackerFileDoesNotHaveOpenShift) => {
+                println!("No session has started.");
+                return;
+            }
+            Err(_) => {
+                panic!("Unexpected error stopping tracking");
+            }
+        };
+
+        let event = HookEvent::new("stop", date, time, shift_duration, self.balance(&document));
+        self.run_pre_hook("pre_stop", &self.config.hooks.pre_stop, &event);
+
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path_buf.as_path(),
+            &document,
+            &format!("Stop shift at {} on {}", time.format("%H:%M"), date),
+        );
+
+        self.run_post_hook("on_stop", &self.config.hooks.on_stop, &event);
+    }
+
+    pub fn cancel_tracking(&self) {
+        let date = self.now.date();
+        let path_buf = self.week_tracker_file(date);
+        let document = match self.read_document(self.week_of(date), path_buf.as_path()) {
+            Ok(document) => document,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                println!("No tracking file for this week has been created.");
+                return;
+            }
+            Err(err) => {
+                panic!("Unexpected error reading document: {}", err);
+            }
+        };
+
+        let Some((shift_date, start_time)) = document.open_shift() else {
+            println!("No session has started.");
+            return;
         };
+        let document = match self.document_with_tracking_cancelled(&document) {
+            Ok(document) => document,
+            Err(_) => {
+                panic!("Unexpected error cancelling tracking");
+            }
+        };
+
+        println!(
+            "Cancelled the shift started at {} on {}.",
+            start_time.format("%H:%M"),
+            shift_date
+        );
+        if document.get_day(shift_date).is_none() {
+            println!("Removed {}, which had nothing else tracked.", shift_date);
+        }
+
+        self.save_document(
+            path_buf.as_path(),
+            &document,
+            &format!(
+                "Cancel shift at {} on {}",
+                start_time.format("%H:%M"),
+                shift_date
+            ),
+        );
+    }
+
+    /// Stops all open shifts that have gone past the configured limits
+    pub fn auto_stop(&self) {
+        let limits = &self.config.limits;
+        if limits.max_shift.is_none() && limits.auto_stop_at.is_none() {
+            eprintln!("No limits are configured, so no shift will ever be stopped automatically.");
+            std::process::exit(1);
+        }
+        for open in self.open_shifts() {
+            let Some(stop_time) = latest_stop_time(open.start_time, limits) else {
+                continue;
+            };
+            if open.date.and_time(stop_time) > self.now {
+                continue;
+            }
+            let document = self.read_existing_document(open.week, &open.path);
+            let day = document
+                .get_day(open.date)
+                .unwrap()
+                .closing_shift(stop_time)
+                .annotating_shift(
+                    open.start_time,
+                    &format!(
+                        "Stopped automatically at {}, the limit for this shift",
+                        stop_time.format("%H:%M")
+                    ),
+                );
+            let message = format!(
+                "Stop shift started at {} on {} automatically at {}",
+                open.start_time.format("%H:%M"),
+                open.date,
+                stop_time.format("%H:%M")
+            );
+            println!("{}.", message);
+            self.save_document(
+                &open.path,
+                &document.replacing_day(open.date, day),
+                &message,
+            );
+        }
+    }
+
+    pub fn start_on_call(&self, time_str: Option<String>) {
+        let date = self.now.date();
+        let time = match time_str {
+            Some(time_str) => self.parse_time(&time_str),
+            None => self.now.time(),
+        };
+        if let Some(open) = self.open_on_calls().first() {
+            eprintln!(
+                "You are already on call since {} on {}. Use `tracker oncall stop` to end it.",
+                open.start_time.format("%H:%M"),
+                open.date
+            );
+            std::process::exit(1);
+        }
+
+        let path_buf =
+            week_tracker_file_create_if_needed(self.week_of(date), self.week_tracker_file(date));
+        let document = self.read_existing_document(self.week_of(date), path_buf.as_path());
+        let document = document.inserting_on_call(
+            date,
+            Line::OnCall {
+                start_time: time,
+                stop_time: None,
+            },
+        );
+
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path_buf.as_path(),
+            &document,
+            &format!("Start on call at {} on {}", time.format("%H:%M"), date),
+        );
+    }
+
+    /// Stops the ongoing on-call period. A period that lasts into a later day than the next
+    /// one, or past the time it started on the next day, is split into a line per day.
+    pub fn stop_on_call(&self, time_str: Option<String>) {
+        let date = self.now.date();
+        let time = match time_str {
+            Some(time_str) => self.parse_time(&time_str),
+            None => self.now.time(),
+        };
+        let Some(open) = self.open_on_calls().into_iter().next() else {
+            println!("You are not on call.");
+            return;
+        };
+
+        if date.and_time(time) < open.date.and_time(open.start_time) {
+            eprintln!(
+                "An on-call period started at {} on {} can not be stopped at {} on {}, before it started.",
+                open.start_time.format("%H:%M"),
+                open.date,
+                time.format("%H:%M"),
+                date
+            );
+            std::process::exit(1);
+        }
+        let message = format!(
+            "Stop on call started at {} on {} at {}",
+            open.start_time.format("%H:%M"),
+            open.date,
+            time.format("%H:%M")
+        );
+
+        let next_day = open.date + TimeDelta::try_days(1).unwrap();
+        let document = self.read_existing_document(open.week, &open.path);
+        if open.date == date || (next_day == date && time <= open.start_time) {
+            let day = document.get_day(open.date).unwrap().closing_on_call(time);
+            let document = document.replacing_day(open.date, day);
+            self.write_day_stdout(&document, open.date);
+            self.save_document(&open.path, &document, &message);
+            return;
+        }
+
+        // The first day is on call until midnight, the days in between all day, and the last
+        // day from midnight, with each day in its own week file
+        let day = document
+            .get_day(open.date)
+            .unwrap()
+            .closing_on_call(NaiveTime::MIN);
+        let mut document = document.replacing_day(open.date, day);
+        self.write_day_stdout(&document, open.date);
+        let mut path = open.path.clone();
+        let mut day = next_day;
+        while day < date || (day == date && time > NaiveTime::MIN) {
+            let day_path = self.week_file_containing(day);
+            if day_path != path {
+                self.save_document(&path, &document, &message);
+                path = week_tracker_file_create_if_needed(self.week_of(day), day_path);
+                document = self.read_existing_document(self.week_of(day), &path);
+            }
+            document = document.inserting_on_call(
+                day,
+                Line::OnCall {
+                    start_time: NaiveTime::MIN,
+                    stop_time: Some(if day == date { time } else { NaiveTime::MIN }),
+                },
+            );
+            self.write_day_stdout(&document, day);
+            day += TimeDelta::try_days(1).unwrap();
+        }
+        self.save_document(&path, &document, &message);
+    }
+
+    pub fn add_shift(&self, date: NaiveDate, range_str: &str) {
+        let (start_time, stop_time) = self.parse_time_range(range_str);
+        let path =
+            week_tracker_file_create_if_needed(self.week_of(date), self.week_file_containing(date));
         let document = self
-            .read_document(date.iso_week(), path_buf.as_path())
+            .read_document(self.week_of(date), path.as_path())
             .unwrap_or_else(|err| {
                 panic!("Unexpected error reading document: {}", err);
             });
 
-        // Validate that start time is not before the end time of the previous shift
-        if let Err(err) = self.validate_start_time(&document, date, time) {
-            eprintln!("{}", err);
-            std::process::exit(1);
+        if let Err(err) = self.validate_shift(&document, date, start_time, Some(stop_time), None) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+
+        let document = document.inserting_shift(
+            date,
+            ClosedShift {
+                start_time,
+                stop_time,
+            },
+        );
+
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path.as_path(),
+            &document,
+            &format!(
+                "Add shift {}-{} on {}",
+                start_time.format("%H:%M"),
+                stop_time.format("%H:%M"),
+                date
+            ),
+        );
+    }
+
+    /// Changes the start or stop time of a shift, given by its date and start time, or the last
+    /// shift of the week if `None`.
+    pub fn amend_shift(
+        &self,
+        shift: Option<(NaiveDate, String)>,
+        start_str: Option<String>,
+        stop_str: Option<String>,
+    ) {
+        if start_str.is_none() && stop_str.is_none() {
+            eprintln!(
+                "Nothing to amend. Give a new start time with --start or a new stop time with --stop."
+            );
+            std::process::exit(1);
+        }
+        let new_start_time = start_str.map(|time_str| self.parse_time(&time_str));
+        let new_stop_time = stop_str.map(|time_str| self.parse_time(&time_str));
+
+        let (path, week) = match &shift {
+            Some((date, _)) => (self.week_file_containing(*date), self.week_of(*date)),
+            None => (
+                self.week_tracker_file(self.now.date()),
+                self.active_week(self.now.date()),
+            ),
+        };
+        let document = self.read_existing_document(week, path.as_path());
+        let (date, line) = match shift {
+            Some((date, time_str)) => {
+                let start_time = self.parse_time(&time_str);
+                match document
+                    .get_day(date)
+                    .and_then(|day| day.get_shift(start_time))
+                {
+                    Some(line) => (date, line.clone()),
+                    None => {
+                        eprintln!(
+                            "There is no shift starting at {} on {}.",
+                            start_time.format("%H:%M"),
+                            date
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
+            None => match document.last_day_with_shift() {
+                Some(day) => (day.date, day.last_shift().unwrap().clone()),
+                None => {
+                    eprintln!("There are no shifts this week.");
+                    std::process::exit(1);
+                }
+            },
+        };
+
+        let old_start_time = line.start_time().unwrap();
+        let amended = match line {
+            OpenShift { start_time } => match new_stop_time {
+                Some(stop_time) => ClosedShift {
+                    start_time: new_start_time.unwrap_or(start_time),
+                    stop_time,
+                },
+                None => OpenShift {
+                    start_time: new_start_time.unwrap_or(start_time),
+                },
+            },
+            ClosedShift {
+                start_time,
+                stop_time,
+            } => ClosedShift {
+                start_time: new_start_time.unwrap_or(start_time),
+                stop_time: new_stop_time.unwrap_or(stop_time),
+            },
+            SpecialShift {
+                text,
+                start_time,
+                stop_time,
+            } => SpecialShift {
+                text,
+                start_time: new_start_time.unwrap_or(start_time),
+                stop_time: new_stop_time.unwrap_or(stop_time),
+            },
+            _ => unreachable!("only shifts have start times"),
+        };
+
+        if let Err(err) = self.validate_shift(
+            &document,
+            date,
+            amended.start_time().unwrap(),
+            amended.stop_time(),
+            Some(old_start_time),
+        ) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+
+        let day = document
+            .get_day(date)
+            .and_then(|day| day.removing_shift(old_start_time))
+            .unwrap()
+            .inserting_shift(amended);
+        let document = document.replacing_day(date, day);
+
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path.as_path(),
+            &document,
+            &format!(
+                "Amend shift at {} on {}",
+                old_start_time.format("%H:%M"),
+                date
+            ),
+        );
+    }
+
+    pub fn remove_shift(&self, date: NaiveDate, time_str: &str) {
+        let start_time = self.parse_time(time_str);
+        let path = self.week_file_containing(date);
+        let document = self.read_existing_document(self.week_of(date), path.as_path());
+
+        let day = match document
+            .get_day(date)
+            .and_then(|day| day.removing_shift(start_time))
+        {
+            Some(day) => day,
+            None => {
+                eprintln!(
+                    "There is no shift starting at {} on {}.",
+                    start_time.format("%H:%M"),
+                    date
+                );
+                std::process::exit(1);
+            }
+        };
+        let document = document.replacing_day(date, day);
+
+        self.write_day_stdout(&document, date);
+
+        self.save_document(
+            path.as_path(),
+            &document,
+            &format!("Remove shift at {} on {}", start_time.format("%H:%M"), date),
+        );
+    }
+
+    pub fn show_weekfile_path(&self) {
+        let date = self.now.date();
+        let path =
+            week_tracker_file_create_if_needed(self.week_of(date), self.week_tracker_file(date));
+        println!("{}", path.display());
+    }
+
+    /// Edits a copy of the week file, which replaces the week file once it can be parsed
+    /// and any warnings about it have been dealt with
+    pub fn edit_file(&self) {
+        let date = self.now.date();
+        let path =
+            week_tracker_file_create_if_needed(self.week_of(date), self.week_tracker_file(date));
+        let name = file_name(&path);
+        let original = fs::read_to_string(&path).expect("Could not read week file");
+        let copy = env::temp_dir().join(format!("tracker-{}-{}", std::process::id(), name));
+        fs::write(&copy, &original).expect("Could not write copy of week file");
+
+        let week = self.active_week(date);
+        let mut line_number = self
+            .parser
+            .try_parse_document(week, &original)
+            .ok()
+            .and_then(|document| {
+                let index = document.days.iter().position(|day| day.date == date)?;
+                Some(document.day_header_line_numbers()[index])
+            });
+        let edited = loop {
+            if let Err(err) = self.run_editor(&copy, line_number) {
+                // Only keep the copy if there are earlier edits in it to lose
+                match fs::read_to_string(&copy) {
+                    Ok(text) if text != original => eprintln!(
+                        "{} The week file was not changed. Your edits are kept in {}.",
+                        err,
+                        copy.display()
+                    ),
+                    _ => {
+                        fs::remove_file(&copy).ok();
+                        eprintln!("{} The week file was not changed.", err);
+                    }
+                }
+                std::process::exit(1);
+            }
+            let edited = fs::read_to_string(&copy).expect("Could not read edited week file");
+            let (problems, can_save) = match self.parser.try_parse_document(week, &edited) {
+                Err(error) => {
+                    line_number = Some(error.line_number);
+                    (vec![format!("{}: {}", name, error)], false)
+                }
+                Ok(document) => {
+                    let warnings = lint::lint(
+                        &document,
+                        self.week_dates(week),
+                        &self.parser.misnamed_day_headers(&edited),
+                    );
+                    if let Some(warning) = warnings.first() {
+                        line_number = Some(warning.line_number);
+                    }
+                    let problems = warnings
+                        .iter()
+                        .map(|warning| {
+                            format!(
+                                "{}: line {}: {}",
+                                name, warning.line_number, warning.message
+                            )
+                        })
+                        .collect::<Vec<String>>();
+                    (problems, true)
+                }
+            };
+            if problems.is_empty() {
+                break edited;
+            }
+            for problem in &problems {
+                eprintln!("{}", problem);
+            }
+            match ask_what_now(can_save) {
+                EditChoice::EditAgain => continue,
+                EditChoice::Save => break edited,
+                EditChoice::Abandon => {
+                    eprintln!(
+                        "The week file was not changed. Your edits are kept in {}.",
+                        copy.display()
+                    );
+                    std::process::exit(1);
+                }
+            }
+        };
+        if edited == original {
+            fs::remove_file(&copy).ok();
+            return;
+        }
+        if let Err(err) = write_atomically(&path, &edited) {
+            eprintln!(
+                "Could not replace {}: {}. Your edits are kept in {}.",
+                path.display(),
+                err,
+                copy.display()
+            );
+            std::process::exit(1);
+        }
+        fs::remove_file(&copy).ok();
+        self.record_change(&path, Some(original), &format!("Edit {}", name));
+    }
+
+    /// Opens a file in the editor, at a certain line if possible, and waits for it to close
+    fn run_editor(&self, path: &Path, line_number: Option<usize>) -> Result<(), String> {
+        let command = editor::editor_command(env::var("VISUAL").ok(), env::var("EDITOR").ok());
+        let status = Command::new(&command[0])
+            .args(&command[1..])
+            .args(editor::editor_args(&command[0], path, line_number))
+            .status()
+            .map_err(|err| format!("Could not run {}: {}.", command[0], err))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{} exited with {}.", command[0], status))
+        }
+    }
+
+    /// The first and last date of a week, unless editing an explicit week file which could
+    /// be for any week
+    fn week_dates(&self, week: IsoWeek) -> Option<(NaiveDate, NaiveDate)> {
+        if self.explicit_weekfile.is_some() {
+            return None;
+        }
+        let first_day = self.workweek().first_day(week);
+        Some((first_day, first_day + TimeDelta::try_days(6).unwrap()))
+    }
+
+    /// Writes a config file with the given or asked for settings, and creates the data
+    /// directory
+    pub fn init(&self, options: InitOptions) {
+        let config_file = self.dirs.config_file();
+        if config_file.exists() && !options.force {
+            eprintln!(
+                "There is already a config file at {}. Use --force to replace it.",
+                config_file.display()
+            );
+            std::process::exit(1);
+        }
+        let answers = InitAnswers::from_options(&options);
+        let data_dir = answers
+            .data_dir
+            .as_deref()
+            .map(expand_tilde)
+            .unwrap_or_else(|| self.dirs.data_dir().to_path_buf());
+
+        if let Some(config_dir) = config_file.parent() {
+            fs::create_dir_all(config_dir).expect("Could not create config directory");
+        }
+        fs::write(config_file, answers.config_text()).expect("Could not write config file");
+        fs::create_dir_all(&data_dir).expect("Could not create data directory");
+        println!("Wrote {}.", config_file.display());
+        println!("Week files will be kept in {}.", data_dir.display());
+    }
+
+    /// Shows which config file and data directory are used, and why
+    pub fn doctor(&self) {
+        let config_file = self.dirs.config_file();
+        println!("Config file:    {}", config_file.display());
+        println!("                from {}", self.dirs.config_origin());
+        if !config_file.exists() {
+            println!("                does not exist, so all settings have their defaults");
+        }
+
+        let data_dir = self.data_dir(self.profile.as_deref());
+        println!("Data directory: {}", data_dir.display());
+        println!("                from {}", self.dirs.data_origin());
+        if let Some(profile) = &self.profile {
+            println!(
+                "                in the {} profile",
+                profile_name(self.profile.as_deref())
+            );
+            if !self.config.profiles.contains_key(profile) {
+                println!(
+                    "                which is not in the config file, add it as [profiles.{}]",
+                    profile
+                );
+            }
+        }
+        if !data_dir.exists() {
+            println!("                does not exist yet, it is created when needed");
+        }
+
+        println!(
+            "Week files:     {} in {}",
+            self.week_file_paths(self.profile.as_deref()).len(),
+            self.week_files_dir().display()
+        );
+    }
+
+    pub fn show_config_path(&self) {
+        println!("{}", self.dirs.config_file().display());
+    }
+
+    /// Shows the effective value of every setting, and whether it comes from the config
+    /// file, the defaults, or for the data directory a flag or an environment variable
+    pub fn show_config(&self) {
+        let settings = settings::effective_settings(&self.config_file_text())
+            .unwrap_or_else(|err| exit_with_settings_error(err));
+        for setting in settings.into_iter().map(|setting| self.in_effect(setting)) {
+            println!(
+                "{:<44} {:<24} {}",
+                setting.key,
+                setting.value.as_deref().unwrap_or("(not set)"),
+                setting.source
+            );
+        }
+    }
+
+    pub fn get_config(&self, key: &str) {
+        let setting = settings::effective_setting(&self.config_file_text(), key)
+            .unwrap_or_else(|err| exit_with_settings_error(err));
+        match self.in_effect(setting).value {
+            Some(value) => println!("{}", value),
+            None => {
+                eprintln!("{} is not set.", key);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Changes a setting in the config file, keeping its comments
+    pub fn set_config(&self, key: &str, value: &str) {
+        let config_file = self.dirs.config_file();
+        let text = settings::setting(&self.config_file_text(), key, value)
+            .unwrap_or_else(|err| exit_with_settings_error(err));
+        if let Some(config_dir) = config_file.parent() {
+            fs::create_dir_all(config_dir).expect("Could not create config directory");
+        }
+        fs::write(config_file, text).expect("Could not write config file");
+        if key == "storage.data_dir"
+            && let origin @ (Origin::Flag(_) | Origin::EnvVar(_)) = self.dirs.data_origin()
+        {
+            println!(
+                "Note that {} takes precedence over the config file.",
+                origin
+            );
+        }
+    }
+
+    /// A setting as it is in effect. The data directory can also be given with a flag or an
+    /// environment variable, so it is taken from where `tracker doctor` says it comes from.
+    fn in_effect(&self, setting: Setting) -> Setting {
+        let source = match self.dirs.data_origin() {
+            Origin::Flag(flag) => Source::Flag(flag),
+            Origin::EnvVar(name) => Source::Env(name),
+            Origin::Setting(_) | Origin::Default => return setting,
+        };
+        if setting.key != "storage.data_dir" {
+            return setting;
+        }
+        Setting {
+            value: Some(settings::string_value(
+                &self.dirs.data_dir().display().to_string(),
+            )),
+            source,
+            ..setting
+        }
+    }
+
+    /// The contents of the config file, which is empty if there is none
+    fn config_file_text(&self) -> String {
+        match fs::read_to_string(self.dirs.config_file()) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => panic!("Unexpected error reading config file: {}", err),
+        }
+    }
+
+    pub fn show_history(&self, week_file_name: Option<String>) {
+        if !self.config.storage.git {
+            eprintln!(
+                "History is not enabled. Set `git = true` in the `[storage]` section of the config file."
+            );
+            std::process::exit(1);
+        }
+        let file_name = week_file_name.map(|name| week_file_name_with_extension(&name));
+        match History::new(&self.week_files_dir()).log(file_name.as_deref()) {
+            Ok(log) => print!("{}", log),
+            Err(err) => {
+                eprintln!("Could not read history: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn restore_week_file(&self, revision: &str, week_file_name: Option<String>) {
+        if !self.config.storage.git {
+            eprintln!(
+                "History is not enabled. Set `git = true` in the `[storage]` section of the config file."
+            );
+            std::process::exit(1);
+        }
+        let file_name = week_file_name
+            .map(|name| week_file_name_with_extension(&name))
+            .unwrap_or_else(|| file_name(&self.week_tracker_file(self.now.date())));
+        let path = self.week_files_dir().join(&file_name);
+        let before = fs::read_to_string(&path).ok();
+        match History::new(&self.week_files_dir()).restore(revision, &file_name) {
+            Ok(()) => {
+                self.record_in_journal(
+                    &path,
+                    before,
+                    &format!("Restore {} from {}", file_name, revision),
+                );
+                println!("Restored {} from {}.", file_name, revision)
+            }
+            Err(err) => {
+                eprintln!("Could not restore {}: {}", file_name, err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn undo(&self) {
+        let journal = self.journal();
+        match journal.undoable() {
+            Ok(Some(entry)) => self.apply_journal_entry(&journal, &entry, EntryKind::Undo),
+            Ok(None) => println!("Nothing to undo."),
+            Err(err) => {
+                eprintln!("Could not read journal: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn redo(&self) {
+        let journal = self.journal();
+        match journal.redoable() {
+            Ok(Some(entry)) => self.apply_journal_entry(&journal, &entry, EntryKind::Redo),
+            Ok(None) => println!("Nothing to redo."),
+            Err(err) => {
+                eprintln!("Could not read journal: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    pub fn show_journal(&self, limit: usize) {
+        let journal = self.journal();
+        let (entries, undone) = match journal
+            .entries()
+            .and_then(|entries| journal.undone().map(|undone| (entries, undone)))
+        {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("Could not read journal: {}", err);
+                std::process::exit(1);
+            }
+        };
+        for entry in entries.iter().rev().take(limit) {
+            let description = match entry.kind {
+                EntryKind::Operation if undone.contains(&entry.id) => {
+                    format!("{} (undone)", entry.description)
+                }
+                EntryKind::Operation => entry.description.clone(),
+                EntryKind::Undo => format!(
+                    "Undo #{}: {}",
+                    entry.target.unwrap_or_default(),
+                    entry.description
+                ),
+                EntryKind::Redo => format!(
+                    "Redo #{}: {}",
+                    entry.target.unwrap_or_default(),
+                    entry.description
+                ),
+            };
+            println!("{:>4}  {}  {}", entry.id, entry.time, description);
+        }
+    }
+
+    /// Reverts an operation (undo) or applies it again (redo), refusing if the week file has
+    /// been changed in other ways since.
+    fn apply_journal_entry(&self, journal: &Journal, entry: &Entry, kind: EntryKind) {
+        let (expected, replacement, verb) = match kind {
+            EntryKind::Redo => (entry.before.clone(), Some(entry.after.clone()), "redone"),
+            _ => (Some(entry.after.clone()), entry.before.clone(), "undone"),
+        };
+        let current = fs::read_to_string(&entry.path).ok();
+        if current != expected {
+            eprintln!(
+                "{} has been changed since \"{}\", so it can not be {}. These changes were made outside of tracker:",
+                entry.path.display(),
+                entry.description,
+                verb
+            );
+            eprint!(
+                "{}",
+                journal::diff(
+                    expected.as_deref().unwrap_or_default(),
+                    current.as_deref().unwrap_or_default()
+                )
+            );
+            std::process::exit(1);
+        }
+
+        match &replacement {
+            Some(content) => {
+                fs::write(&entry.path, content).expect("Could not write document to file")
+            }
+            None => fs::remove_file(&entry.path).expect("Could not remove file"),
+        }
+        if let Err(err) = journal.append(Entry {
+            id: 0,
+            time: self.now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            kind,
+            description: entry.description.clone(),
+            path: entry.path.clone(),
+            before: current,
+            after: replacement.unwrap_or_default(),
+            target: Some(entry.id),
+        }) {
+            eprintln!("Could not record change in journal: {}", err);
+        }
+        let action = match kind {
+            EntryKind::Redo => "Redo",
+            _ => "Undo",
+        };
+        self.commit_history(&format!("{}: {}", action, entry.description));
+        println!("{}: {}", action, entry.description);
+    }
+
+    pub fn show_report(
+        &self,
+        is_working: bool,
+        days: bool,
+        billing: bool,
+        stale: Option<StaleResolution>,
+    ) {
+        if !is_working {
+            self.resolve_stale_open_shifts(stale, false);
+        }
+        let week = self.active_week(self.now.date());
+        let path =
+            week_tracker_file_create_if_needed(week, self.week_tracker_file(self.now.date()));
+        match self.read_document(week, &path) {
+            Ok(document) => {
+                self.process_report_of_document(document, self.now, is_working, days, billing)
+            }
+            Err(err) => eprintln!("Error: {}", err),
+        }
+    }
+
+    /// Shows the time worked today and during the week, and the balance, of every profile
+    pub fn show_combined_report(&self) {
+        let date = self.now.date();
+        let mut total_today = Duration::zero();
+        let mut total_week = Duration::zero();
+        let mut tracking: Vec<&str> = Vec::new();
+        println!(
+            "{:<16} {:>8} {:>8} {:>8}",
+            "Profile", "Today", "Week", "Balance"
+        );
+        for profile in self.profiles() {
+            let workweek = self.config.workweek_for(profile);
+            let week = workweek.week_of(
+                self.weekdiff
+                    .map(|d| date + TimeDelta::try_days(d as i64 * 7).unwrap())
+                    .unwrap_or(date),
+            );
+            let path = self.week_tracker_file_for_date(profile, date, self.weekdiff);
+            let document = match self.read_document(week, &path) {
+                Ok(document) => document,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Document::empty(week),
+                Err(err) => {
+                    panic!("Unexpected error reading document: {}", err);
+                }
+            };
+            let report = Report::from_document(
+                &document,
+                &self.now,
+                &ReportSettings::new(&self.config, profile),
+            );
+            println!(
+                "{:<16} {:>8} {:>8} {:>8}",
+                profile_name(profile),
+                format_hours_minutes(&report.duration_today),
+                format_hours_minutes(&report.duration_week),
+                format_hours_minutes(&report.balance)
+            );
+            total_today += report.duration_today;
+            total_week += report.duration_week;
+            if report.is_ongoing {
+                tracking.push(profile_name(profile));
+            }
+        }
+        println!(
+            "{:<16} {:>8} {:>8}",
+            "Total",
+            format_hours_minutes(&total_today),
+            format_hours_minutes(&total_week)
+        );
+        for profile in tracking {
+            println!("Tracking in the {} profile.", profile);
+        }
+    }
+
+    pub fn show_days(&self, weekdiff: Option<i32>, date: Option<NaiveDate>, compact: bool) {
+        let date_in_week = date.unwrap_or_else(|| {
+            weekdiff
+                .or(self.weekdiff)
+                .map(|d| self.now.date() + TimeDelta::try_days(d as i64 * 7).unwrap())
+                .unwrap_or(self.now.date())
+        });
+        let week = self.week_of(date_in_week);
+        let path = self.week_file_containing(date_in_week);
+        let document = match self.read_document(week, path.as_path()) {
+            Ok(document) => document,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Document::empty(week),
+            Err(err) => {
+                panic!("Unexpected error reading document: {}", err);
+            }
+        };
+        let width = terminal_size::terminal_size()
+            .map(|(terminal_size::Width(width), _)| width as usize)
+            .unwrap_or(80);
+        let options = ShowOptions {
+            compact,
+            width,
+            only_date: date,
+        };
+        print!(
+            "{}",
+            show::render(&document, &self.now, &self.report_settings(), &options)
+        );
+    }
+
+    pub fn export_ics(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+        let (from, to) = self.date_range(from, to, self.current_week());
+        let stamp = self
+            .now
+            .and_local_timezone(Local)
+            .earliest()
+            .map(|now| now.naive_utc())
+            .unwrap_or(self.now);
+        print!(
+            "{}",
+            export::to_ics(&self.days_in_range(from, to), self.workweek(), stamp)
+        );
+    }
+
+    /// Exports a timesheet, by default over the current month
+    pub fn export_csv(&self, from: Option<NaiveDate>, to: Option<NaiveDate>, options: CsvOptions) {
+        let (from, to) = self.date_range(from, to, self.current_month());
+        print!(
+            "{}",
+            export::to_csv(
+                &self.days_in_range(from, to),
+                self.workweek(),
+                &self.config.billing,
+                &options
+            )
+        );
+    }
+
+    /// Checks the tracked time against the working-time rules, by default over the reference
+    /// period up until today
+    pub fn check_compliance(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+        let reference_weeks = self.config.compliance.reference_weeks as i64;
+        let from = from.unwrap_or_else(|| {
+            self.now.date() - TimeDelta::try_weeks(reference_weeks - 1).unwrap()
+        });
+        let to = to.unwrap_or(self.now.date());
+        let violations = self.compliance_violations(from, to);
+        if violations.is_empty() {
+            println!("No violations from {} to {}.", from, to);
+        }
+        for violation in &violations {
+            println!("{}", self.describe_violation(violation));
+        }
+    }
+
+    /// Shows the time worked within each supplement window, by default over the current month
+    pub fn show_supplements(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+        if self.config.supplements.is_empty() {
+            eprintln!("No supplements are configured");
+            std::process::exit(1);
+        }
+        let (from, to) = self.date_range(from, to, self.current_month());
+        let days = supplements::supplements_per_day(
+            &self.days_in_range(from, to),
+            &self.config.supplements,
+            &self.workweek().holidays,
+        );
+        print!(
+            "{}",
+            supplements::render_table(&days, &self.config.supplements)
+        );
+    }
+
+    fn compliance_violations(&self, from: NaiveDate, to: NaiveDate) -> Vec<Violation> {
+        let reference_weeks = self.config.compliance.reference_weeks as i64;
+        let days = self.days_in_range(
+            from - TimeDelta::try_weeks(reference_weeks + 1).unwrap(),
+            to,
+        );
+        let now = self
+            .now
+            .min((to + TimeDelta::try_days(1).unwrap()).and_time(NaiveTime::MIN));
+        compliance::check(&days, from, now, &self.config.compliance, self.workweek())
+    }
+
+    fn describe_violation(&self, violation: &Violation) -> String {
+        let compliance = &self.config.compliance;
+        let format_shift = |shift: &WorkedShift| {
+            format!(
+                "{} {}-{}",
+                shift.start.date(),
+                shift.start.format("%H:%M"),
+                shift.stop.format("%H:%M")
+            )
+        };
+        match violation {
+            Violation::DailyRest {
+                before,
+                after,
+                rest,
+            } => format!(
+                "Only {} of rest between {} and {}, at least {} is required.",
+                format_duration(rest),
+                format_shift(before),
+                format_shift(after),
+                format_duration(&compliance.daily_rest)
+            ),
+            Violation::WeeklyRest { week, longest_rest } => format!(
+                "The longest rest in week {} of {} was {}, at least {} is required.",
+                week.week(),
+                week.year(),
+                format_duration(longest_rest),
+                format_duration(&compliance.weekly_rest)
+            ),
+            Violation::AverageWeek {
+                first_week,
+                last_week,
+                average,
+            } => format!(
+                "Worked {} per week on average from week {} of {} to week {} of {}, at most {} is allowed.",
+                format_duration(average),
+                first_week.week(),
+                first_week.year(),
+                last_week.week(),
+                last_week.year(),
+                format_duration(&compliance.max_average_week)
+            ),
         }
+    }
 
-        let document = match self.document_with_tracking_started(&document, date, time) {
-            Ok(doc) => doc,
-            Err(DocumentError::TrackerFileAlreadyHasOpenShift) => {
-                eprintln!(
-                    "You are already tracking. Use `tracker stop` to end the current shift. If you forgot to stop tracking earlier, use `tracker edit`."
-                );
-                std::process::exit(1);
-            }
-            Err(_) => {
-                panic!("Unexpected error starting tracking");
-            }
-        };
+    /// Resolves an optional date range, defaulting to the start and end of another range
+    fn date_range(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        (default_from, default_to): (NaiveDate, NaiveDate),
+    ) -> (NaiveDate, NaiveDate) {
+        let from = from.unwrap_or(default_from);
+        let to = to.unwrap_or(default_to);
+        if to < from {
+            eprintln!(
+                "The end of the date range ({}) is before its start ({})",
+                to, from
+            );
+            std::process::exit(1);
+        }
+        (from, to)
+    }
 
-        self.write_day_stdout(&document, date);
+    /// The first and last day of the active week
+    fn current_week(&self) -> (NaiveDate, NaiveDate) {
+        let first_day = self.workweek().first_day(self.active_week(self.now.date()));
+        (first_day, first_day + TimeDelta::try_days(6).unwrap())
+    }
 
-        fs::write(path_buf.as_path(), document.to_string())
-            .expect("Could not write document to file");
+    /// The first and last day of the current month
+    fn current_month(&self) -> (NaiveDate, NaiveDate) {
+        let first_day = self.now.date().with_day(1).unwrap();
+        let next_month = first_day
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap();
+        (first_day, next_month - TimeDelta::try_days(1).unwrap())
     }
 
-    pub fn stop_tracking(&self) {
-        let date = self.now.date();
-        let time = self.now.time();
-        let path_buf = self.week_tracker_file(date);
-        let document = match self.read_document(date.iso_week(), path_buf.as_path()) {
-            Ok(document) => document,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                println!("No tracking file for this week has been created.");
-                return;
+    /// All days from the week files covering a date range, in date order
+    fn days_in_range(&self, from: NaiveDate, to: NaiveDate) -> Vec<Day> {
+        let mut paths: Vec<(IsoWeek, PathBuf)> = Vec::new();
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            let path = self.week_file_containing(date);
+            if !paths.iter().any(|(_, p)| *p == path) {
+                paths.push((self.week_of(date), path));
             }
-            Err(err) => {
-                panic!("Unexpected error reading document: {}", err);
+        }
+
+        let mut days: Vec<Day> = Vec::new();
+        for (week, path) in paths {
+            match self.read_document(week, path.as_path()) {
+                Ok(document) => days.extend(
+                    document
+                        .days
+                        .into_iter()
+                        .filter(|day| day.date >= from && day.date <= to),
+                ),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    panic!("Unexpected error reading document: {}", err);
+                }
             }
+        }
+        days.sort_by_key(|day| day.date);
+        days
+    }
+
+    /// The week file for the week of a date, unless an explicit week file has been given
+    fn week_file_containing(&self, date: NaiveDate) -> PathBuf {
+        self.explicit_weekfile
+            .clone()
+            .unwrap_or_else(|| self.week_tracker_file_for_date(self.profile.as_deref(), date, None))
+    }
+
+    /// All week files of a profile, including an explicit one for the current profile, in order
+    fn week_file_paths(&self, profile: Option<&str>) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = match fs::read_dir(self.week_files_dir_of(profile)) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+                .collect(),
+            Err(_) => vec![],
         };
+        if let Some(explicit_weekfile) = &self.explicit_weekfile
+            && profile == self.profile.as_deref()
+            && !paths.contains(explicit_weekfile)
+        {
+            paths.push(explicit_weekfile.clone());
+        }
+        paths.sort();
+        paths
+    }
 
-        let document = match self.document_with_tracking_stopped(&document, date, time) {
-            Ok(document) => document,
-            Err(DocumentError::TrackerFileDoesNotHaveOpenShift) => {
-                println!("No session has started.");
-                return;
+    /// The week files of a profile, newest first, with an explicit one for the current
+    /// profile before the others
+    fn week_file_paths_newest_first(&self, profile: Option<&str>) -> Vec<PathBuf> {
+        let explicit_weekfile = self
+            .explicit_weekfile
+            .as_ref()
+            .filter(|_| profile == self.profile.as_deref());
+        let mut paths: Vec<PathBuf> = self
+            .week_file_paths(profile)
+            .into_iter()
+            .filter(|path| Some(path) != explicit_weekfile)
+            .collect();
+        paths.reverse();
+        if let Some(explicit_weekfile) = explicit_weekfile {
+            paths.insert(0, explicit_weekfile.clone());
+        }
+        paths
+    }
+
+    /// The open lines that `find` returns in the week files of a profile. Lines are only
+    /// left open in the last week that anything was tracked in, so the week files are looked
+    /// in newest first until one with any days in it. Week files that can't be parsed are
+    /// skipped with a warning.
+    fn find_in_week_files(
+        &self,
+        profile: Option<&str>,
+        find: impl Fn(&Document) -> Option<(NaiveDate, NaiveTime)>,
+    ) -> Vec<OpenLineInFile> {
+        let mut found = Vec::new();
+        for path in self.week_file_paths_newest_first(profile) {
+            let week =
+                week_of_week_file(&path).unwrap_or_else(|| self.active_week(self.now.date()));
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let document = match self.parser.try_parse_document(week, &content) {
+                Ok(document) => document,
+                Err(error) => {
+                    eprintln!("Warning: Skipping {}: {}", path.display(), error);
+                    continue;
+                }
+            };
+            if let Some((date, start_time)) = find(&document) {
+                found.push(OpenLineInFile {
+                    path,
+                    week,
+                    date,
+                    start_time,
+                });
             }
-            Err(_) => {
-                panic!("Unexpected error stopping tracking");
+            if !document.days.is_empty() {
+                break;
             }
-        };
+        }
+        found
+    }
 
-        self.write_day_stdout(&document, date);
+    /// Shifts that are still open, in the last week that anything was tracked in
+    fn open_shifts(&self) -> Vec<OpenLineInFile> {
+        self.find_in_week_files(self.profile.as_deref(), Document::open_shift)
+    }
 
-        fs::write(path_buf.as_path(), document.to_string())
-            .expect("Could not write document to file");
+    /// On-call periods that are still open, in the last week that anything was tracked in
+    fn open_on_calls(&self) -> Vec<OpenLineInFile> {
+        self.find_in_week_files(self.profile.as_deref(), Document::open_on_call)
     }
 
-    pub fn show_weekfile_path(&self) {
-        let date = self.now.date();
-        let path =
-            week_tracker_file_create_if_needed(date.iso_week(), self.week_tracker_file(date));
-        println!("{}", path.display());
+    /// Exits if a shift is open in another profile, as one can only track one thing at a time
+    fn exit_if_tracking_in_other_profile(&self) {
+        for profile in self.profiles() {
+            if profile == self.profile.as_deref() {
+                continue;
+            }
+            if let Some(open) = self
+                .find_in_week_files(profile, Document::open_shift)
+                .first()
+            {
+                eprintln!(
+                    "You are already tracking in the {} profile, since {} on {}. Stop that shift first with `tracker{} stop`.",
+                    profile_name(profile),
+                    open.start_time.format("%H:%M"),
+                    open.date,
+                    profile
+                        .map(|profile| format!(" --profile {}", profile))
+                        .unwrap_or_default()
+                );
+                std::process::exit(1);
+            }
+        }
     }
 
-    pub fn edit_file(&self) {
-        let path = week_tracker_file_create_if_needed(
-            self.now.iso_week(),
-            self.week_tracker_file(self.now.date()),
-        );
+    /// Shifts that are still open on a day before today
+    fn stale_open_shifts(&self) -> Vec<OpenLineInFile> {
+        self.open_shifts()
+            .into_iter()
+            .filter(|open| open.date < self.now.date())
+            .collect()
+    }
 
-        let editor = env::var("EDITOR").unwrap();
-        Command::new(editor)
-            .arg(&path)
-            .status()
-            .expect("Could not open editor");
+    /// Resolves shifts left open on earlier days. Without a given resolution, the user is
+    /// asked what to do if `ask` is set and we're in a terminal, and otherwise just warned.
+    /// If asking is needed but not possible, we exit.
+    fn resolve_stale_open_shifts(&self, resolution: Option<StaleResolution>, ask: bool) {
+        for stale in self.stale_open_shifts() {
+            let description = format!(
+                "The shift started at {} on {} was never stopped.",
+                stale.start_time.format("%H:%M"),
+                stale.date
+            );
+            let resolution = match (&resolution, ask) {
+                (Some(resolution), _) => resolution.clone(),
+                (None, true) if io::stdin().is_terminal() => {
+                    println!("{}", description);
+                    self.ask_for_stale_resolution()
+                }
+                (None, true) => {
+                    eprintln!(
+                        "{} Resolve it with --close-stale HH:MM, --close-stale-at-default or --discard-stale.",
+                        description
+                    );
+                    std::process::exit(1);
+                }
+                (None, false) => {
+                    eprintln!(
+                        "Warning: {} Resolve it with --close-stale HH:MM, --close-stale-at-default or --discard-stale.",
+                        description
+                    );
+                    continue;
+                }
+            };
+            self.apply_stale_resolution(&stale, &resolution);
+        }
     }
 
-    pub fn show_report(&self, is_working: bool) {
-        let path = week_tracker_file_create_if_needed(
-            self.active_week(self.now.date()),
-            self.week_tracker_file(self.now.date()),
-        );
-        let result = fs::read_to_string(path);
-        match result {
-            Ok(content) => self.process_report_of_content(content, self.now, is_working),
-            Err(err) => eprintln!("Error: {}", err),
+    fn ask_for_stale_resolution(&self) -> StaleResolution {
+        let default_end_time = self.workweek().default_end_time.format("%H:%M");
+        loop {
+            print!(
+                "Close it at a time (HH:MM), close it at {} (d), discard it (x) or abort (a)? ",
+                default_end_time
+            );
+            io::stdout().flush().expect("Could not write to stdout");
+            let mut answer = String::new();
+            if io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+                std::process::exit(1);
+            }
+            match answer.trim() {
+                "d" => return StaleResolution::CloseAtDefault,
+                "x" => return StaleResolution::Discard,
+                "a" | "" => std::process::exit(1),
+                time_str if NaiveTime::parse_from_str(time_str, "%H:%M").is_ok() => {
+                    return StaleResolution::CloseAt(String::from(time_str));
+                }
+                _ => println!("Please answer with a time like 17:00, or d, x or a."),
+            }
         }
     }
 
+    fn apply_stale_resolution(&self, stale: &OpenLineInFile, resolution: &StaleResolution) {
+        let document = self.read_existing_document(stale.week, &stale.path);
+        let stop_time = match resolution {
+            StaleResolution::CloseAt(time_str) => Some(self.parse_time(time_str)),
+            StaleResolution::CloseAtDefault => Some(self.workweek().default_end_time),
+            StaleResolution::Discard => None,
+        };
+        let (document, message) = match stop_time {
+            Some(stop_time) => {
+                if let Err(err) = self.validate_shift(
+                    &document,
+                    stale.date,
+                    stale.start_time,
+                    Some(stop_time),
+                    Some(stale.start_time),
+                ) {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+                let day = document
+                    .get_day(stale.date)
+                    .unwrap()
+                    .closing_shift(stop_time);
+                (
+                    document.replacing_day(stale.date, day),
+                    format!(
+                        "Close shift started at {} on {} at {}",
+                        stale.start_time.format("%H:%M"),
+                        stale.date,
+                        stop_time.format("%H:%M")
+                    ),
+                )
+            }
+            None => (
+                self.document_with_tracking_cancelled(&document)
+                    .expect("document has an open shift"),
+                format!(
+                    "Discard shift started at {} on {}",
+                    stale.start_time.format("%H:%M"),
+                    stale.date
+                ),
+            ),
+        };
+        println!("{}.", message);
+        self.save_document(&stale.path, &document, &message);
+    }
+
     fn week_tracker_file(&self, date: NaiveDate) -> PathBuf {
-        self.explicit_weekfile
-            .clone()
-            .unwrap_or_else(|| self.week_tracker_file_for_date(date, self.weekdiff))
+        self.explicit_weekfile.clone().unwrap_or_else(|| {
+            self.week_tracker_file_for_date(self.profile.as_deref(), date, self.weekdiff)
+        })
     }
 
     // transfer only happens from previous week when no explicit week file or week diff has been set
     fn week_to_transfer_from(&self, date: NaiveDate) -> Option<IsoWeek> {
         if self.explicit_weekfile.is_none() && self.weekdiff.is_none() {
-            Some((date - TimeDelta::try_days(7).unwrap()).iso_week())
+            Some(self.week_of(date - TimeDelta::try_days(7).unwrap()))
         } else {
             None
         }
     }
 
+    /// A new document for a week, starting with the balance and overtime bank carried over
+    /// from the latest week file up until `last_week`
+    fn document_with_transfer(&self, week: IsoWeek, last_week: Option<IsoWeek>) -> Document {
+        let Some(last_week) = last_week else {
+            return Document::empty(week);
+        };
+        let latest = fs::read_dir(self.week_files_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|path| week_of_week_file(&path).map(|week| (week, path)))
+            .filter(|(week, _)| *week <= last_week)
+            .max_by_key(|(week, _)| *week);
+        let Some((last_week, path)) = latest else {
+            return Document::empty(week);
+        };
+        let last_document = self.read_existing_document(last_week, &path);
+        let last_day = self.workweek().first_day(last_week) + TimeDelta::try_days(6).unwrap();
+        let report = Report::from_document(
+            &last_document,
+            &last_day.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()),
+            &self.report_settings(),
+        );
+        let carry_over = flex::carry_over(
+            report.balance,
+            last_day,
+            self.workweek().first_day(week),
+            &self.config.flex,
+        );
+
+        let mut preamble = vec![Line::DurationShift {
+            text: String::from("balance"),
+            duration: report.balance,
+        }];
+        if !carry_over.forfeited.is_zero() {
+            preamble.push(Line::DurationShift {
+                text: String::from("forfeited"),
+                duration: -carry_over.forfeited,
+            });
+        }
+        if !report.overtime_bank.is_zero() {
+            preamble.push(Line::DurationShift {
+                text: String::from("overtime"),
+                duration: report.overtime_bank,
+            });
+        }
+        preamble.push(Line::Blank);
+        Document::new(week, preamble, vec![])
+    }
+
     fn read_document(&self, week: IsoWeek, path: &Path) -> io::Result<Document> {
         match fs::read_to_string(path) {
-            Ok(content) => Result::Ok(self.parser.parse_document(week, &content)),
+            Ok(content) => match self.parser.try_parse_document(week, &content) {
+                Ok(document) => Ok(document),
+                Err(error) => {
+                    eprintln!("Could not read {}: {}", path.display(), error);
+                    std::process::exit(1);
+                }
+            },
             Err(err) => Result::Err(err),
         }
     }
 
-    fn get_report(&self, content: String, now: NaiveDateTime) -> Report {
-        let document = self
-            .parser
-            .parse_document(self.active_week(now.date()), &content);
-        Report::from_document(&document, &now, &self.config.workweek)
+    /// Reads a document, exiting with a message if there is no such week file
+    fn read_existing_document(&self, week: IsoWeek, path: &Path) -> Document {
+        match self.read_document(week, path) {
+            Ok(document) => document,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                eprintln!("There is no week file at {}.", path.display());
+                std::process::exit(1);
+            }
+            Err(err) => {
+                panic!("Unexpected error reading document: {}", err);
+            }
+        }
     }
 
-    fn process_report_of_content(&self, content: String, now: NaiveDateTime, is_working: bool) {
-        let report = self.get_report(content, now);
+    fn process_report_of_document(
+        &self,
+        document: Document,
+        now: NaiveDateTime,
+        is_working: bool,
+        days: bool,
+        billing: bool,
+    ) {
+        let report = Report::from_document(&document, &now, &self.report_settings());
         if is_working {
             let code = match report.is_ongoing {
                 true => 0,
@@ -176,20 +1647,127 @@ impl Tracker {
             std::process::exit(code);
         }
 
-        print!(
-            "You have worked {} today",
-            format_duration(&report.duration_today)
-        );
-        if report.is_ongoing {
-            println!(", ongoing.")
+        let locale = self.config.locale;
+        let week = self.active_week(now.date());
+        if week == self.week_of(now.date()) {
+            println!(
+                "{}",
+                locale.worked_today(&format_duration(&report.duration_today), report.is_ongoing)
+            );
+            println!(
+                "{}",
+                locale.worked_this_week(&format_duration(&report.duration_week))
+            );
         } else {
-            println!(".")
+            println!(
+                "{}",
+                locale.worked_in_week(
+                    &format_duration(&report.duration_week),
+                    week.week(),
+                    week.year()
+                )
+            );
+        }
+        println!("{}", locale.balance(&format_duration(&report.balance)));
+        if !report.forfeited.is_zero() {
+            println!("{}", locale.forfeited(&format_duration(&report.forfeited)));
+        }
+        if !report.overtime.is_zero() || !report.overtime_bank.is_zero() {
+            println!(
+                "{}",
+                locale.overtime(
+                    &format_duration(&report.overtime),
+                    &format_duration(&report.overtime_bank)
+                )
+            );
+        }
+        if !report.on_call.standby.is_zero() || !report.on_call.incidents.is_zero() {
+            println!(
+                "{}",
+                locale.on_call(
+                    &format_duration(&report.on_call.standby),
+                    &format_duration(&report.on_call.incidents),
+                    &format_duration(&report.on_call.credited)
+                )
+            );
+        }
+
+        if days {
+            let [date, worked, credited, expected, diff] = locale.day_table_headers();
+            println!();
+            println!(
+                "{:<14} {:>8} {:>8} {:>8} {:>8}",
+                date, worked, credited, expected, diff
+            );
+            for day in &report.days {
+                println!(
+                    "{:<14} {:>8} {:>8} {:>8} {:>8}",
+                    format_table_date(day.date, locale),
+                    format_hours_minutes(&day.worked),
+                    format_hours_minutes(&day.credited),
+                    format_hours_minutes(&day.expected),
+                    format_hours_minutes(&day.diff)
+                );
+            }
+        }
+
+        if billing {
+            println!();
+            self.print_billing(&document.days);
+        }
+
+        if self.config.compliance.in_report {
+            let last_week = self.week_of(now.date() - TimeDelta::try_weeks(1).unwrap());
+            let violations =
+                self.compliance_violations(self.workweek().first_day(last_week), now.date());
+            if !violations.is_empty() {
+                println!();
+                println!("{}", locale.warnings());
+                for violation in &violations {
+                    println!("  {}", self.describe_violation(violation));
+                }
+            }
+        }
+
+        let event = HookEvent::new("report", now.date(), now.time(), None, report.balance);
+        self.run_post_hook("on_report", &self.config.hooks.on_report, &event);
+    }
+
+    fn print_billing(&self, days: &[Day]) {
+        let entries = billing::billed_entries(days, self.workweek(), &self.config.billing);
+        let locale = self.config.locale;
+        let [date, kind, raw, rounded, total] = locale.billing_table_headers();
+        println!("{:<14} {:<12} {:>8} {:>8}", date, kind, raw, rounded);
+        for entry in &entries {
+            println!(
+                "{:<14} {:<12} {:>8} {:>8}",
+                format_table_date(entry.date, locale),
+                entry.kind,
+                format_hours_minutes(&entry.raw),
+                format_hours_minutes(&entry.rounded)
+            );
         }
         println!(
-            "You have worked {} this week.",
-            format_duration(&report.duration_week)
+            "{:<14} {:<12} {:>8} {:>8}",
+            total,
+            "",
+            format_hours_minutes(&entries.iter().map(|entry| entry.raw).sum()),
+            format_hours_minutes(&entries.iter().map(|entry| entry.rounded).sum())
         );
-        println!("Balance: {}", format_duration(&report.balance))
+    }
+
+    /// Records the UTC offset of a day if the local clock is not in the home time zone
+    fn with_local_offset(&self, document: Document, date: NaiveDate) -> Document {
+        let offset = self.local_offset.and_then(|local_offset| {
+            timezone::offset_away_from_home(self.now, local_offset, self.workweek())
+        });
+        match (offset, document.get_day(date)) {
+            (Some(offset), Some(day)) => {
+                let day = day.with_utc_offset(offset);
+                document.replacing_day(date, day)
+            }
+            _ => document,
+        }
     }
 
     pub fn document_with_tracking_started(
@@ -224,11 +1802,100 @@ impl Tracker {
         Err(DocumentError::TrackerFileDoesNotHaveOpenShift)
     }
 
+    fn balance(&self, document: &Document) -> Duration {
+        Report::from_document(document, &self.now, &self.report_settings()).balance
+    }
+
+    /// Runs a hook that may veto what is about to happen, by failing
+    fn run_pre_hook(&self, name: &str, hook: &Option<String>, event: &HookEvent) {
+        if let Some(command) = hook
+            && let Err(err) = hooks::run_hook(command, event)
+        {
+            eprintln!("The {} hook stopped this: {}", name, err);
+            std::process::exit(1);
+        }
+    }
+
+    /// Runs a hook after something has happened, only warning if it fails
+    fn run_post_hook(&self, name: &str, hook: &Option<String>, event: &HookEvent) {
+        if let Some(command) = hook
+            && let Err(err) = hooks::run_hook(command, event)
+        {
+            eprintln!("Warning: the {} hook failed: {}", name, err);
+        }
+    }
+
+    /// Writes a document to its week file, recording the change in the history if enabled
+    fn save_document(&self, path: &Path, document: &Document, message: &str) {
+        let before = fs::read_to_string(path).ok();
+        fs::write(path, document.to_string_in(self.config.locale))
+            .expect("Could not write document to file");
+        self.record_change(path, before, message);
+    }
+
+    /// Records a change to a week file in the journal, and in the history if enabled
+    fn record_change(&self, path: &Path, before: Option<String>, message: &str) {
+        self.record_in_journal(path, before, message);
+        self.commit_history(message);
+    }
+
+    fn record_in_journal(&self, path: &Path, before: Option<String>, description: &str) {
+        let after = fs::read_to_string(path).unwrap_or_default();
+        if before.as_ref() == Some(&after) {
+            return;
+        }
+        let entry = Entry {
+            id: 0,
+            time: self.now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            kind: EntryKind::Operation,
+            description: String::from(description),
+            path: path.to_path_buf(),
+            before,
+            after,
+            target: None,
+        };
+        if let Err(err) = self.journal().append(entry) {
+            eprintln!("Could not record change in journal: {}", err);
+        }
+    }
+
+    fn journal(&self) -> Journal {
+        Journal::new(&self.data_dir(self.profile.as_deref()).join("journal.toml"))
+    }
+
+    fn commit_history(&self, message: &str) {
+        if !self.config.storage.git {
+            return;
+        }
+        if let Err(err) = History::new(&self.week_files_dir()).commit(message) {
+            eprintln!("Could not record change in history: {}", err);
+        }
+    }
+
+    /// Removes the open shift, and its day if nothing else is left in it
+    pub fn document_with_tracking_cancelled(
+        &self,
+        document: &Document,
+    ) -> Result<Document, DocumentError> {
+        let Some((date, start_time)) = document.open_shift() else {
+            return Err(DocumentError::TrackerFileDoesNotHaveOpenShift);
+        };
+        let day = document
+            .get_day(date)
+            .and_then(|day| day.removing_shift(start_time))
+            .ok_or(DocumentError::TrackerFileDoesNotHaveOpenShift)?;
+        if day.lines.iter().all(|line| *line == Line::Blank) {
+            Ok(document.removing_day(date))
+        } else {
+            Ok(document.replacing_day(date, day))
+        }
+    }
+
     fn write_day_stdout(&self, document: &Document, date: NaiveDate) {
         let day = document
             .get_day(date)
             .expect("this should be called right after day is modified");
-        print!("{}", day.to_string())
+        print!("{}", day.to_string_in(self.config.locale))
     }
 
     fn parse_time(&self, time_str: &str) -> NaiveTime {
@@ -254,68 +1921,156 @@ impl Tracker {
         })
     }
 
-    fn validate_start_time(
+    fn parse_time_range(&self, range_str: &str) -> (NaiveTime, NaiveTime) {
+        match range_str.split_once('-') {
+            Some((start_str, stop_str)) => (self.parse_time(start_str), self.parse_time(stop_str)),
+            None => {
+                eprintln!("Invalid time range format. Expected HH:MM-HH:MM (e.g., 08:30-12:00)");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Validates that a shift ends after it starts and does not overlap any other shift on
+    /// the same day, optionally ignoring the shift it replaces. A shift without stop time
+    /// is considered to go on for the rest of the day.
+    fn validate_shift(
         &self,
         document: &Document,
         date: NaiveDate,
-        time: NaiveTime,
+        start_time: NaiveTime,
+        stop_time: Option<NaiveTime>,
+        ignoring: Option<NaiveTime>,
     ) -> Result<(), String> {
-        // Find the last closed shift on the same date or before
-        let mut last_end_time: Option<(NaiveDate, NaiveTime)> = None;
-
-        for day in &document.days {
-            if day.date > date {
-                break;
-            }
-
-            for line in &day.lines {
-                match line {
-                    Line::ClosedShift { stop_time, .. } => {
-                        last_end_time = Some((day.date, *stop_time));
-                    }
-                    Line::SpecialShift { stop_time, .. } => {
-                        last_end_time = Some((day.date, *stop_time));
-                    }
-                    _ => {}
-                }
-            }
+        if let Some(stop_time) = stop_time
+            && stop_time <= start_time
+        {
+            return Err(format!(
+                "Stop time {} is not after start time {}",
+                stop_time.format("%H:%M"),
+                start_time.format("%H:%M")
+            ));
         }
 
-        if let Some((last_date, last_time)) = last_end_time {
-            // If the last shift was on the same day
-            if last_date == date && time < last_time {
-                return Err(format!(
-                    "Start time {} is before the end time {} of the previous shift on the same day",
-                    time.format("%H:%M"),
-                    last_time.format("%H:%M")
-                ));
+        let Some(day) = document.get_day(date) else {
+            return Ok(());
+        };
+        for line in &day.lines {
+            let Some(other_start_time) = line.start_time() else {
+                continue;
+            };
+            if ignoring == Some(other_start_time) {
+                continue;
+            }
+            let starts_before_other_stops = line
+                .stop_time()
+                .is_none_or(|other_stop_time| start_time < other_stop_time);
+            let other_starts_before_stop =
+                stop_time.is_none_or(|stop_time| other_start_time < stop_time);
+            if starts_before_other_stops && other_starts_before_stop {
+                return Err(match (stop_time, line.stop_time()) {
+                    (None, Some(other_stop_time)) => format!(
+                        "Start time {} is before the end time {} of the shift starting at {} on {}",
+                        start_time.format("%H:%M"),
+                        other_stop_time.format("%H:%M"),
+                        other_start_time.format("%H:%M"),
+                        date
+                    ),
+                    _ => format!(
+                        "The shift overlaps the shift starting at {} on {}",
+                        other_start_time.format("%H:%M"),
+                        date
+                    ),
+                });
             }
-            // If the last shift was on a previous day, we don't need to validate
-            // (shifts can start earlier on a new day)
         }
 
         Ok(())
     }
 
+    /// Validates that a shift can be started at a time, which it can't if that is during or
+    /// before another shift on the same day. An open shift is left for starting to refuse.
+    fn validate_start_time(
+        &self,
+        document: &Document,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<(), String> {
+        let open_shift_start_time = document
+            .get_day(date)
+            .and_then(|day| {
+                day.lines
+                    .iter()
+                    .find(|line| matches!(line, OpenShift { .. }))
+            })
+            .and_then(Line::start_time);
+        self.validate_shift(document, date, time, None, open_shift_start_time)
+    }
+
     fn active_week(&self, date: NaiveDate) -> IsoWeek {
-        self.weekdiff
-            .map(|d| date + TimeDelta::try_days(d as i64 * 7).unwrap())
-            .unwrap_or(date)
-            .iso_week()
+        self.week_of(
+            self.weekdiff
+                .map(|d| date + TimeDelta::try_days(d as i64 * 7).unwrap())
+                .unwrap_or(date),
+        )
+    }
+
+    /// The week that a date is tracked in, which depends on the day weeks start on
+    fn week_of(&self, date: NaiveDate) -> IsoWeek {
+        self.workweek().week_of(date)
     }
 
-    fn week_tracker_file_for_date(&self, date: NaiveDate, weekdiff: Option<i32>) -> PathBuf {
+    fn week_tracker_file_for_date(
+        &self,
+        profile: Option<&str>,
+        date: NaiveDate,
+        weekdiff: Option<i32>,
+    ) -> PathBuf {
         let date = weekdiff
             .map(|d| date + TimeDelta::try_days(d as i64 * 7).unwrap())
             .unwrap_or(date);
 
-        self.dirs
-            .data_dir()
-            .join("week-files")
-            .join(date.format("%Y-W%W.txt").to_string())
+        let filing_date = self.config.workweek_for(profile).filing_date(date);
+        self.week_files_dir_of(profile)
+            .join(filing_date.format("%Y-W%W.txt").to_string())
+    }
+
+    fn week_files_dir(&self) -> PathBuf {
+        self.week_files_dir_of(self.profile.as_deref())
+    }
+
+    fn week_files_dir_of(&self, profile: Option<&str>) -> PathBuf {
+        self.data_dir(profile).join("week-files")
+    }
+
+    /// The data directory of a profile, or of the default profile if `None`
+    fn data_dir(&self, profile: Option<&str>) -> PathBuf {
+        match profile {
+            Some(profile) => self.dirs.data_dir().join("profiles").join(profile),
+            None => self.dirs.data_dir().to_path_buf(),
+        }
+    }
+
+    fn workweek(&self) -> &WorkWeekConfig {
+        self.config.workweek_for(self.profile.as_deref())
+    }
+
+    fn report_settings(&self) -> ReportSettings<'_> {
+        ReportSettings::new(&self.config, self.profile.as_deref())
+    }
+
+    /// All profiles, starting with the default profile as `None`
+    fn profiles(&self) -> Vec<Option<&str>> {
+        std::iter::once(None)
+            .chain(self.config.profiles.keys().map(|name| Some(name.as_str())))
+            .collect()
     }
 }
 
+fn profile_name(profile: Option<&str>) -> &str {
+    profile.unwrap_or("default")
+}
+
 #[derive(Debug, Clone)]
 pub enum DocumentError {
     TrackerFileAlreadyHasOpenShift,
@@ -335,6 +2090,8 @@ pub struct TrackerBuilder {
     now: Option<NaiveDateTime>,
     dirs: Option<TrackerDirs>,
     config: Option<Config>,
+    profile: Option<String>,
+    local_offset: Option<FixedOffset>,
 }
 
 impl TrackerBuilder {
@@ -363,24 +2120,40 @@ impl TrackerBuilder {
         self
     }
 
+    /// The UTC offset of the local clock, which is recorded for days away from the home
+    /// time zone
+    pub fn local_offset(mut self, local_offset: FixedOffset) -> Self {
+        self.local_offset = Some(local_offset);
+        self
+    }
+
+    /// Use the week files and settings of a named profile instead of the default one
+    pub fn profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
     pub fn build(self) -> Tracker {
+        let config = self.config.unwrap_or_default();
         Tracker {
             explicit_weekfile: self.explicit_weekfile,
             weekdiff: self.weekdiff,
-            parser: Parser::new(),
+            parser: Parser::new(config.locale),
             now: self.now.expect("now value required"),
             dirs: self.dirs.expect("dirs value expected"),
-            config: self.config.unwrap_or_default(),
+            config,
+            profile: self.profile,
+            local_offset: self.local_offset,
         }
     }
 }
 
 // Week tracker file
 
-fn week_tracker_file_create_if_needed_with_transfer(
-    week: IsoWeek,
+/// Creates the week file with an initial document, unless it already exists
+fn week_tracker_file_create_if_needed_with(
     path: PathBuf,
-    last_week: Option<IsoWeek>,
+    initial_document: impl FnOnce() -> Document,
 ) -> PathBuf {
     // Create parents if needed
     if let Some(parent_path) = path.parent() {
@@ -389,8 +2162,7 @@ fn week_tracker_file_create_if_needed_with_transfer(
 
     match OpenOptions::new().write(true).create_new(true).open(&path) {
         Ok(mut file) => {
-            let initial_document = default_document(week, last_week);
-            file.write_all(initial_document.to_string().as_bytes())
+            file.write_all(initial_document().to_string().as_bytes())
                 .expect("Could not write example document to file");
         }
         Err(err) => {
@@ -403,18 +2175,33 @@ fn week_tracker_file_create_if_needed_with_transfer(
     path
 }
 
-fn default_document(week: IsoWeek, last_week: Option<IsoWeek>) -> Document {
-    if let Some(_last_week) = last_week {
-        // let content = fs::read_to_string(path).expect("Could not read last week file");
-        // let last_week_document = Parser::new().parse_document(last_week, &content);
-        // return Document::empty_with_balance(last_week_document);
-        return Document::empty(week);
-    }
-    Document::empty(week)
+fn week_tracker_file_create_if_needed(week: IsoWeek, path: PathBuf) -> PathBuf {
+    week_tracker_file_create_if_needed_with(path, || Document::empty(week))
 }
 
-fn week_tracker_file_create_if_needed(week: IsoWeek, path: PathBuf) -> PathBuf {
-    week_tracker_file_create_if_needed_with_transfer(week, path, None)
+/// The week of a week file named like `2024-W04.txt`
+fn week_of_week_file(path: &Path) -> Option<IsoWeek> {
+    let stem = path.file_stem()?.to_str()?;
+    // Week 0 is the days before the first monday of the year, so it might not have a monday
+    NaiveDate::parse_from_str(&format!("{}-1", stem), "%Y-W%W-%u")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-7", stem), "%Y-W%W-%u"))
+        .ok()
+        .map(|date| date.iso_week())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Week files can be referred to with or without extension, e.g. `2024-W04`
+fn week_file_name_with_extension(name: &str) -> String {
+    if name.ends_with(".txt") {
+        String::from(name)
+    } else {
+        format!("{}.txt", name)
+    }
 }
 
 #[cfg(test)]
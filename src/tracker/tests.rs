@@ -262,6 +262,21 @@ fn validation_fails_when_start_time_is_before_previous_shift_end_time() {
     assert!(result.unwrap_err().contains("before the end time"));
 }
 
+#[test]
+fn validation_fails_when_start_time_is_before_a_later_shift() {
+    let tracker = build_tracker().build();
+    let result = tracker.validate_start_time(
+        &document_with_morning_and_afternoon(),
+        naive_date(2019, 12, 2),
+        naive_time(12, 30),
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .contains("before the end time 17:00 of the shift starting at 13:00")
+    );
+}
+
 #[test]
 fn validation_passes_when_starting_shift_on_new_day_even_if_earlier_than_previous_day() {
     let tracker = build_tracker().build();
@@ -291,3 +306,176 @@ fn validation_passes_when_no_previous_shifts_exist() {
 fn build_tracker() -> TrackerBuilder {
     Tracker::builder(naive_date_time(2023, 12, 2, 12, 0), TrackerDirs::real())
 }
+
+#[test]
+fn shift_validation_passes_between_other_shifts() {
+    let tracker = build_tracker().build();
+    let result = tracker.validate_shift(
+        &document_with_morning_and_afternoon(),
+        naive_date(2019, 12, 2),
+        naive_time(12, 0),
+        Some(naive_time(13, 0)),
+        None,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn shift_validation_fails_when_overlapping_another_shift() {
+    let tracker = build_tracker().build();
+    let result = tracker.validate_shift(
+        &document_with_morning_and_afternoon(),
+        naive_date(2019, 12, 2),
+        naive_time(11, 30),
+        Some(naive_time(13, 30)),
+        None,
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .contains("overlaps the shift starting at 08:00")
+    );
+}
+
+#[test]
+fn shift_validation_ignores_the_shift_being_amended() {
+    let tracker = build_tracker().build();
+    let result = tracker.validate_shift(
+        &document_with_morning_and_afternoon(),
+        naive_date(2019, 12, 2),
+        naive_time(7, 45),
+        Some(naive_time(12, 0)),
+        Some(naive_time(8, 0)),
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn shift_validation_fails_when_stop_is_not_after_start() {
+    let tracker = build_tracker().build();
+    let result = tracker.validate_shift(
+        &Document::empty(naive_date(2019, 12, 2).iso_week()),
+        naive_date(2019, 12, 2),
+        naive_time(12, 0),
+        Some(naive_time(11, 0)),
+        None,
+    );
+    assert!(result.unwrap_err().contains("is not after start time"));
+}
+
+#[test]
+fn shift_validation_fails_when_starting_after_an_open_shift() {
+    let tracker = build_tracker().build();
+    let document = Document::new(
+        naive_date(2019, 12, 2).iso_week(),
+        vec![],
+        vec![Day {
+            date: naive_date(2019, 12, 2),
+            lines: vec![Line::OpenShift {
+                start_time: naive_time(8, 0),
+            }],
+        }],
+    );
+    let result = tracker.validate_shift(
+        &document,
+        naive_date(2019, 12, 2),
+        naive_time(12, 0),
+        Some(naive_time(13, 0)),
+        None,
+    );
+    assert!(result.is_err());
+}
+
+fn document_with_morning_and_afternoon() -> Document {
+    Document::new(
+        naive_date(2019, 12, 2).iso_week(),
+        vec![],
+        vec![Day {
+            date: naive_date(2019, 12, 2),
+            lines: vec![
+                Line::ClosedShift {
+                    start_time: naive_time(8, 0),
+                    stop_time: naive_time(12, 0),
+                },
+                Line::ClosedShift {
+                    start_time: naive_time(13, 0),
+                    stop_time: naive_time(17, 0),
+                },
+            ],
+        }],
+    )
+}
+
+#[test]
+fn cancelling_undoes_starting_on_a_new_day() {
+    let tracker = build_tracker().build();
+    let document = Document::new(
+        naive_date(2019, 12, 2).iso_week(),
+        vec![],
+        vec![Day {
+            date: naive_date(2019, 12, 2),
+            lines: vec![Line::ClosedShift {
+                start_time: naive_time(10, 0),
+                stop_time: naive_time(10, 30),
+            }],
+        }],
+    );
+    let started = tracker
+        .document_with_tracking_started(&document, naive_date(2019, 12, 3), naive_time(8, 0))
+        .unwrap();
+
+    let cancelled = tracker.document_with_tracking_cancelled(&started).unwrap();
+
+    assert_eq!(document, cancelled);
+}
+
+#[test]
+fn cancelling_keeps_the_rest_of_the_day() {
+    let tracker = build_tracker().build();
+    let document = Document::new(
+        naive_date(2019, 12, 2).iso_week(),
+        vec![],
+        vec![Day {
+            date: naive_date(2019, 12, 2),
+            lines: vec![
+                Line::ClosedShift {
+                    start_time: naive_time(10, 0),
+                    stop_time: naive_time(10, 30),
+                },
+                Line::Blank,
+            ],
+        }],
+    );
+    let started = tracker
+        .document_with_tracking_started(&document, naive_date(2019, 12, 2), naive_time(12, 0))
+        .unwrap();
+
+    let cancelled = tracker.document_with_tracking_cancelled(&started).unwrap();
+
+    assert_eq!(document, cancelled);
+}
+
+#[test]
+fn we_can_not_cancel_without_open_shift() {
+    let tracker = build_tracker().build();
+    let result = tracker
+        .document_with_tracking_cancelled(&Document::empty(naive_date(2019, 12, 2).iso_week()));
+    assert!(result.is_err());
+}
+
+#[test]
+fn week_of_week_file_is_read_from_file_name() {
+    assert_eq!(
+        Some(naive_date(2024, 1, 22).iso_week()),
+        super::week_of_week_file(std::path::Path::new("/data/2024-W04.txt"))
+    );
+    // Week 0 of 2023 is just sunday 2023-01-01, which is in ISO week 52 of 2022
+    assert_eq!(
+        Some(naive_date(2023, 1, 1).iso_week()),
+        super::week_of_week_file(std::path::Path::new("2023-W00.txt"))
+    );
+    assert_eq!(
+        None,
+        super::week_of_week_file(std::path::Path::new("notes.txt"))
+    );
+}
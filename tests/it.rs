@@ -1,10 +1,19 @@
+use std::collections::BTreeMap;
+use std::fs;
 use std::path::PathBuf;
+use std::process::Command;
 
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use temp_dir::TempDir;
 use tracker::{
+    config::{
+        Config, FeaturesConfig, FlexConfig, HooksConfig, LimitsConfig, ProfileConfig,
+        StorageConfig, WorkWeekConfig, read_config_from_path,
+    },
+    init::InitOptions,
+    locale::Locale,
     paths::TrackerDirs,
-    tracker::{Tracker, TrackerBuilder},
+    tracker::{StaleResolution, Tracker, TrackerBuilder},
 };
 
 struct TrackerTestContext {
@@ -51,7 +60,486 @@ fn read_file_and_report() {
         .builder
         .explicit_weekfile(Some(test_data().join("2024-W04.txt")))
         .build();
-    tracker.show_report(false)
+    tracker.show_report(false, false, false, None)
+}
+
+#[test]
+fn read_file_and_report_days() {
+    let ctx = ctx();
+    let tracker = ctx
+        .builder
+        .explicit_weekfile(Some(test_data().join("2024-W04.txt")))
+        .build();
+    tracker.show_report(false, true, false, None)
+}
+
+#[test]
+fn start_and_stop_are_recorded_in_git_history() {
+    let ctx = ctx();
+    let git_config = || Config {
+        storage: StorageConfig {
+            git: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 8, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()),
+    )
+    .config(git_config())
+    .build()
+    .start_tracking(None, None);
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 12, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()),
+    )
+    .config(git_config())
+    .build()
+    .stop_tracking();
+
+    let log = Command::new("git")
+        .arg("-C")
+        .arg(ctx.tempdir.path().join("data").join("week-files"))
+        .args(["log", "--format=%s"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        "Stop shift at 12:00 on 2024-01-22\nStart shift at 08:00 on 2024-01-22\n",
+        String::from_utf8_lossy(&log.stdout)
+    );
+}
+
+#[test]
+fn stopping_can_be_undone_and_redone() {
+    let ctx = ctx();
+    let tracker_at = |hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, 22, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .build()
+    };
+    let week_file = ctx
+        .tempdir
+        .path()
+        .join("data")
+        .join("week-files")
+        .join("2024-W04.txt");
+
+    tracker_at(8).start_tracking(None, None);
+    tracker_at(12).stop_tracking();
+    tracker_at(13).undo();
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+
+    tracker_at(13).redo();
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-12:00\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+}
+
+#[test]
+fn stale_shift_is_closed_at_default_end_time_when_starting() {
+    let ctx = ctx();
+    let tracker_at = |day, hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, day, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .build()
+    };
+    let week_files = ctx.tempdir.path().join("data").join("week-files");
+
+    tracker_at(19, 8).start_tracking(None, None);
+    tracker_at(22, 9).start_tracking(None, Some(StaleResolution::CloseAtDefault));
+
+    assert_eq!(
+        "[friday 2024-01-19]\n* 08:00-17:00\n",
+        fs::read_to_string(week_files.join("2024-W03.txt")).unwrap()
+    );
+    assert_eq!(
+        "[monday 2024-01-22]\n* 09:00-\n",
+        fs::read_to_string(week_files.join("2024-W04.txt")).unwrap()
+    );
+}
+
+#[test]
+fn profiles_have_their_own_week_files() {
+    let ctx = ctx();
+    let tracker_at = |hour, profile: Option<&str>| {
+        Tracker::builder(
+            naive_date_time(2024, 1, 22, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            profiles: BTreeMap::from([(String::from("consulting"), ProfileConfig::default())]),
+            ..Default::default()
+        })
+        .profile(profile.map(String::from))
+        .build()
+    };
+    let data = ctx.tempdir.path().join("data");
+
+    tracker_at(8, None).start_tracking(None, None);
+    tracker_at(9, None).stop_tracking();
+    tracker_at(10, Some("consulting")).start_tracking(None, None);
+
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-09:00\n",
+        fs::read_to_string(data.join("week-files").join("2024-W04.txt")).unwrap()
+    );
+    assert_eq!(
+        "[monday 2024-01-22]\n* 10:00-\n",
+        fs::read_to_string(
+            data.join("profiles")
+                .join("consulting")
+                .join("week-files")
+                .join("2024-W04.txt")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn offset_is_recorded_when_away_from_home() {
+    let ctx = ctx();
+    let week_file = ctx
+        .tempdir
+        .path()
+        .join("data")
+        .join("week-files")
+        .join("2024-W04.txt");
+
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 9, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()),
+    )
+    .config(Config {
+        workweek: WorkWeekConfig {
+            time_zone: Some(chrono_tz::Europe::Stockholm),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .local_offset(FixedOffset::east_opt(9 * 3600).unwrap())
+    .build()
+    .start_tracking(None, None);
+
+    assert_eq!(
+        "[monday 2024-01-22]\n* 09:00-\n* offset +09:00\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+}
+
+#[test]
+fn weeks_starting_on_sunday_are_filed_with_the_following_days() {
+    let ctx = ctx();
+    let tracker_at = |day, hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, day, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            workweek: WorkWeekConfig {
+                week_start: chrono::Weekday::Sun,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build()
+    };
+    let week_files = ctx.tempdir.path().join("data").join("week-files");
+
+    tracker_at(21, 8).start_tracking(None, None);
+    tracker_at(21, 12).stop_tracking();
+    tracker_at(22, 8).start_tracking(None, None);
+
+    assert_eq!(
+        "[sunday 2024-01-21]\n* 08:00-12:00\n\n[monday 2024-01-22]\n* 08:00-\n",
+        fs::read_to_string(week_files.join("2024-W04.txt")).unwrap()
+    );
+}
+
+#[test]
+fn day_headers_are_written_in_the_configured_locale() {
+    let ctx = ctx();
+    let week_file = ctx
+        .tempdir
+        .path()
+        .join("data")
+        .join("week-files")
+        .join("2024-W04.txt");
+    let tracker_at = |day, hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, day, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            locale: Locale::Swedish,
+            ..Default::default()
+        })
+        .build()
+    };
+    fs::create_dir_all(week_file.parent().unwrap()).unwrap();
+    fs::write(&week_file, "[monday 2024-01-22]\n* 08:00-12:00\n").unwrap();
+
+    tracker_at(27, 10).start_tracking(None, None);
+
+    assert_eq!(
+        "[måndag 2024-01-22]\n* 08:00-12:00\n\n[lördag 2024-01-27]\n* 10:00-\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+}
+
+#[test]
+fn stale_shifts_are_looked_for_in_the_latest_week_file_that_can_be_parsed() {
+    let ctx = ctx();
+    let week_files = ctx.tempdir.path().join("data").join("week-files");
+    fs::create_dir_all(&week_files).unwrap();
+    fs::write(week_files.join("2024-W03.txt"), "[someday 2024-01-15]\n").unwrap();
+    fs::write(
+        week_files.join("2024-W01.txt"),
+        "[friday 2024-01-05]\n* 08:00-\n",
+    )
+    .unwrap();
+    fs::write(
+        week_files.join("2023-W02.txt"),
+        "[monday 2023-01-09]\n* 08:00-\n",
+    )
+    .unwrap();
+
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 9, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()),
+    )
+    .build()
+    .start_tracking(None, Some(StaleResolution::CloseAtDefault));
+
+    assert_eq!(
+        "[friday 2024-01-05]\n* 08:00-17:00\n",
+        fs::read_to_string(week_files.join("2024-W01.txt")).unwrap()
+    );
+    assert_eq!(
+        "[monday 2023-01-09]\n* 08:00-\n",
+        fs::read_to_string(week_files.join("2023-W02.txt")).unwrap()
+    );
+    assert_eq!(
+        "[monday 2024-01-22]\n* 09:00-\n",
+        fs::read_to_string(week_files.join("2024-W04.txt")).unwrap()
+    );
+}
+
+#[test]
+fn stale_shift_can_be_discarded_when_reporting() {
+    let ctx = ctx();
+    let tracker_at = |day, hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, day, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .build()
+    };
+    let week_file = ctx
+        .tempdir
+        .path()
+        .join("data")
+        .join("week-files")
+        .join("2024-W04.txt");
+
+    tracker_at(22, 8).start_tracking(None, None);
+    tracker_at(22, 12).stop_tracking();
+    tracker_at(23, 8).start_tracking(None, None);
+    tracker_at(24, 9).show_report(false, false, false, Some(StaleResolution::Discard));
+
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-12:00\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+}
+
+#[test]
+fn auto_stop_closes_shifts_past_the_limit() {
+    let ctx = ctx();
+    let tracker_at = |hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, 22, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            limits: LimitsConfig {
+                max_shift: chrono::Duration::try_hours(10),
+                auto_stop_at: None,
+            },
+            ..Default::default()
+        })
+        .build()
+    };
+    let week_file = ctx
+        .tempdir
+        .path()
+        .join("data")
+        .join("week-files")
+        .join("2024-W04.txt");
+
+    tracker_at(8).start_tracking(None, None);
+    tracker_at(17).auto_stop();
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+
+    tracker_at(19).auto_stop();
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-18:00\n# Stopped automatically at 18:00, the limit for this shift\n",
+        fs::read_to_string(&week_file).unwrap()
+    );
+}
+
+#[test]
+fn hooks_are_run_when_starting_and_stopping() {
+    let ctx = ctx();
+    let log = ctx.tempdir.path().join("hooks.log");
+    let tracker_at = |hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, 22, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            hooks: HooksConfig {
+                on_start: Some(format!(
+                    "echo \"$TRACKER_EVENT $TRACKER_TIME\" >> {}",
+                    log.display()
+                )),
+                on_stop: Some(format!(
+                    "echo \"$TRACKER_EVENT $TRACKER_TIME $TRACKER_SHIFT_MINUTES\" >> {}",
+                    log.display()
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build()
+    };
+
+    tracker_at(8).start_tracking(None, None);
+    tracker_at(12).stop_tracking();
+
+    assert_eq!(
+        "start 08:00\nstop 12:00 240\n",
+        fs::read_to_string(&log).unwrap()
+    );
+}
+
+#[test]
+fn the_stop_hook_gets_the_length_of_the_shift_as_the_report_counts_it() {
+    let ctx = ctx();
+    let log = ctx.tempdir.path().join("hooks.log");
+    let tracker_at = |hour| {
+        Tracker::builder(
+            naive_date_time(2024, 3, 31, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .config(Config {
+            workweek: WorkWeekConfig {
+                time_zone: Some(chrono_tz::Europe::Stockholm),
+                ..Default::default()
+            },
+            hooks: HooksConfig {
+                on_stop: Some(format!(
+                    "echo \"$TRACKER_SHIFT_MINUTES\" >> {}",
+                    log.display()
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .build()
+    };
+
+    // The clocks are turned forward an hour at 02:00
+    tracker_at(1).start_tracking(None, None);
+    tracker_at(4).stop_tracking();
+
+    assert_eq!("120\n", fs::read_to_string(&log).unwrap());
+}
+
+#[test]
+fn balance_is_carried_over_within_floor() {
+    let ctx = ctx();
+    let week_files = ctx.tempdir.path().join("data").join("week-files");
+    fs::create_dir_all(&week_files).unwrap();
+    fs::write(
+        week_files.join("2024-W03.txt"),
+        "* balance 9h 0m\n\n[friday 2024-01-19]\n* 08:00-10:00\n",
+    )
+    .unwrap();
+
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 8, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()),
+    )
+    .config(Config {
+        experimental_features: FeaturesConfig {
+            auto_transfer_balance: true,
+        },
+        flex: FlexConfig {
+            floor: Some(chrono::Duration::hours(-20)),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .build()
+    .start_tracking(None, None);
+
+    // 9 hours in, 2 hours worked and 40 hours expected
+    assert_eq!(
+        "* balance -29h 0m\n* forfeited 9h 0m\n\n[monday 2024-01-22]\n* 08:00-\n",
+        fs::read_to_string(week_files.join("2024-W04.txt")).unwrap()
+    );
+}
+
+#[test]
+fn init_writes_config_and_creates_data_dir() {
+    let ctx = ctx();
+    let dirs = TrackerDirs::fixed(ctx.tempdir.path());
+    let data_dir = ctx.tempdir.path().join("synced");
+
+    ctx.builder.build().init(InitOptions {
+        hours_per_day: Some(6),
+        locale: Some(Locale::Swedish),
+        data_dir: Some(data_dir.clone()),
+        ..Default::default()
+    });
+
+    let config = read_config_from_path(dirs.config_file()).unwrap();
+    assert_eq!(config.workweek.hours_per_day, 6);
+    assert_eq!(config.workweek.days_per_week, 5);
+    assert_eq!(config.locale, Locale::Swedish);
+    assert_eq!(config.storage.data_dir, Some(data_dir.clone()));
+    assert!(data_dir.is_dir());
+}
+
+#[test]
+fn week_files_are_kept_in_the_configured_data_dir() {
+    let ctx = ctx();
+    let synced = ctx.tempdir.path().join("synced");
+
+    Tracker::builder(
+        naive_date_time(2024, 1, 22, 8, 0),
+        TrackerDirs::fixed(ctx.tempdir.path()).with_data_dir(None, None, Some(synced.clone())),
+    )
+    .build()
+    .start_tracking(None, None);
+
+    assert_eq!(
+        "[monday 2024-01-22]\n* 08:00-\n",
+        fs::read_to_string(synced.join("week-files").join("2024-W04.txt")).unwrap()
+    );
+    assert!(!ctx.tempdir.path().join("data").exists());
 }
 
 #[test]
@@ -60,3 +548,159 @@ fn no_op_test() {
     let _tracker = ctx.builder.build();
     assert_eq!(true, true);
 }
+
+#[test]
+fn a_vetoed_start_leaves_no_week_file_behind() {
+    let tempdir = TempDir::new().unwrap();
+    let config_file = tempdir.path().join("config.toml");
+    let data_dir = tempdir.path().join("data");
+    fs::write(&config_file, "[hooks]\npre_start = \"false\"\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tracker"))
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .arg("start")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(!data_dir.join("week-files").exists());
+}
+
+#[test]
+fn on_call_periods_of_several_days_are_split_per_day() {
+    let ctx = ctx();
+    let tracker_at = |day, hour| {
+        Tracker::builder(
+            naive_date_time(2024, 1, day, hour, 0),
+            TrackerDirs::fixed(ctx.tempdir.path()),
+        )
+        .build()
+    };
+    let week_files = ctx.tempdir.path().join("data").join("week-files");
+
+    tracker_at(27, 17).start_on_call(None);
+    tracker_at(29, 8).stop_on_call(None);
+
+    assert_eq!(
+        "[saturday 2024-01-27]\n* oncall 17:00-00:00\n\n[sunday 2024-01-28]\n* oncall 00:00-00:00\n",
+        fs::read_to_string(week_files.join("2024-W04.txt")).unwrap()
+    );
+    assert_eq!(
+        "[monday 2024-01-29]\n* oncall 00:00-08:00\n",
+        fs::read_to_string(week_files.join("2024-W05.txt")).unwrap()
+    );
+}
+
+#[test]
+fn an_invalid_config_file_can_be_replaced_with_init() {
+    let tempdir = TempDir::new().unwrap();
+    let config_file = tempdir.path().join("config.toml");
+    fs::write(&config_file, "[workweek]\nhours_per_day = \"eight\"\n").unwrap();
+    let tracker = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_tracker"))
+            .arg("--config")
+            .arg(&config_file)
+            .arg("--data-dir")
+            .arg(tempdir.path().join("data"))
+            .arg("--profile")
+            .arg("missing")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    assert!(!tracker(&["report"]).status.success());
+    assert!(tracker(&["doctor"]).status.success());
+    assert!(tracker(&["init", "--no-input", "--force"]).status.success());
+    read_config_from_path(&config_file).unwrap();
+}
+
+#[test]
+fn config_and_doctor_agree_on_where_the_data_directory_comes_from() {
+    let tempdir = TempDir::new().unwrap();
+    let config_file = tempdir.path().join("config.toml");
+    let data_dir = tempdir.path().join("synced");
+    fs::write(&config_file, "[storage]\ndata_dir = \"/elsewhere\"\n").unwrap();
+    let tracker = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_tracker"))
+            .arg("--config")
+            .arg(&config_file)
+            .env("TRACKER_DATA_DIR", &data_dir)
+            .args(args)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        format!("\"{}\"\n", data_dir.display()),
+        tracker(&["config", "get", "storage.data_dir"])
+    );
+    assert!(
+        tracker(&["config", "show"]).contains("env TRACKER_DATA_DIR"),
+        "config show"
+    );
+    assert!(
+        tracker(&["doctor"]).contains("from the TRACKER_DATA_DIR environment variable"),
+        "doctor"
+    );
+}
+
+#[test]
+fn edits_are_kept_when_the_editor_fails_after_changing_the_copy() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tempdir = TempDir::new().unwrap();
+    let editor = tempdir.path().join("editor.sh");
+    fs::write(&editor, "#!/bin/sh\necho '# edited' >> \"$1\"\nexit 1\n").unwrap();
+    fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+    let edit = |visual: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_tracker"))
+            .arg("--config")
+            .arg(tempdir.path().join("config.toml"))
+            .arg("--data-dir")
+            .arg(tempdir.path().join("data"))
+            .arg("edit")
+            .env("VISUAL", visual)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    assert!(!edit("false").contains("kept in"));
+
+    let message = edit(editor.to_str().unwrap());
+    let copy = message
+        .trim_end()
+        .strip_suffix('.')
+        .and_then(|message| message.split_once("Your edits are kept in "))
+        .map(|(_, copy)| PathBuf::from(copy))
+        .expect(&message);
+    assert!(fs::read_to_string(&copy).unwrap().contains("# edited"));
+    fs::remove_file(copy).unwrap();
+}
+
+#[test]
+fn a_week_file_that_can_not_be_read_is_reported_without_panicking() {
+    let tempdir = TempDir::new().unwrap();
+    let week_file = tempdir.path().join("2026-W42.txt");
+    fs::write(&week_file, "[lördag 2026-10-17]\n* 08:00-12:00\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tracker"))
+        .arg("--config")
+        .arg(tempdir.path().join("config.toml"))
+        .arg("--data-dir")
+        .arg(tempdir.path().join("data"))
+        .arg("--weekfile")
+        .arg(&week_file)
+        .arg("report")
+        .output()
+        .unwrap();
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 1"));
+}